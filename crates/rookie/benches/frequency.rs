@@ -1,7 +1,10 @@
 use peak_alloc::PeakAlloc;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use rookie::prover::prove_dummy;
-use stwo::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+use rookie::{
+    air::components::DummyConfig,
+    prover::{prove_dummy, verify_dummy},
+};
+use stwo::core::{pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel};
 
 #[global_allocator]
 static PEAK_ALLOC: PeakAlloc = PeakAlloc;
@@ -18,14 +21,46 @@ const N: &[usize] = &[2_usize.pow(12), 2_usize.pow(13)];
     sample_count = 1
 )]
 fn bench_frequency<const N: usize>(bencher: divan::Bencher, log_size: u32) {
+    let configs = [DummyConfig::new(log_size, N)];
     bencher.bench(|| {
         PEAK_ALLOC.reset_peak_usage();
-        (0..10)
+        let proofs = (0..10)
             .into_par_iter()
-            .map(|_| prove_dummy::<Blake2sMerkleChannel, N>(log_size))
-            .collect::<Vec<_>>();
+            .map(|_| prove_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
         let peak_bytes = PEAK_ALLOC.peak_usage_as_mb();
         println!("Peak memory: {peak_bytes} MB");
+        println!("Proof size: {} bytes", proofs[0].size_bytes());
         divan::black_box(peak_bytes);
     });
 }
+
+#[divan::bench(
+    consts = N,
+    args = [13, 14],
+    sample_count = 10
+)]
+fn bench_verify<const N: usize>(bencher: divan::Bencher, log_size: u32) {
+    let configs = [DummyConfig::new(log_size, N)];
+    let proof = prove_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default()).unwrap();
+    println!("Proof size: {} bytes", proof.size_bytes());
+    bencher.with_inputs(|| proof.clone()).bench_values(|proof| {
+        verify_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default(), proof).unwrap()
+    });
+}
+
+#[divan::bench(args = [1, 4, 16], sample_count = 1)]
+fn bench_frequency_lookups(bencher: divan::Bencher, lookup_batch_size: usize) {
+    let config = DummyConfig {
+        n_constraints: 64,
+        constraint_degree: 2,
+        n_lookups: 64,
+        lookup_batch_size,
+        n_preprocessed_cols: 8,
+        ..DummyConfig::new(13, 1 << 10)
+    };
+    let mut pcs_config = PcsConfig::default();
+    pcs_config.fri_config.log_blowup_factor = config.log_degree();
+    bencher.bench(|| prove_dummy::<Blake2sMerkleChannel>(&[config.clone()], pcs_config));
+}
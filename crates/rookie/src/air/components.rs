@@ -1,22 +1,166 @@
-use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval};
+use num_traits::One;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
+use stwo_constraint_framework::{
+    preprocessed_columns::PreProcessedColumnId, relation, EvalAtRow, FrameworkComponent,
+    FrameworkEval, RelationEntry,
+};
 
 pub type DummyComponent = FrameworkComponent<DummyEval>;
 
-#[derive(Clone)]
-pub struct DummyEval {
+relation!(DummyRelation, 1);
+
+/// Shape of a synthetic component, to measure each cost of an AIR separately.
+///
+/// The last `n_constraints` trace columns are each constrained to the product of
+/// `constraint_degree` of the other columns. Each lookup adds a trace column to [`DummyRelation`],
+/// `lookup_batch_size` lookups sharing an interaction column. The preprocessed columns are read
+/// but not constrained.
+///
+/// The log blowup factor of the PCS config must be at least [`DummyConfig::log_degree`], see
+/// [`DummyConfig::check_log_blowup_factor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DummyConfig {
     pub log_size: u32,
     pub n_cols: usize,
+    pub n_constraints: usize,
+    pub constraint_degree: usize,
+    pub n_lookups: usize,
+    pub lookup_batch_size: usize,
+    pub n_preprocessed_cols: usize,
+}
+
+impl DummyConfig {
+    /// `n_cols` trace columns, without constraints, lookups nor preprocessed columns.
+    pub fn new(log_size: u32, n_cols: usize) -> Self {
+        Self {
+            log_size,
+            n_cols,
+            n_constraints: 0,
+            constraint_degree: 1,
+            n_lookups: 0,
+            lookup_batch_size: 1,
+            n_preprocessed_cols: 0,
+        }
+    }
+
+    /// Log of the degree of the constraints, rounded up, and at least 1.
+    pub fn log_degree(&self) -> u32 {
+        let lookup_degree = if self.n_lookups > 0 {
+            self.lookup_batch_size + 1
+        } else {
+            0
+        };
+        self.constraint_degree
+            .max(lookup_degree)
+            .max(2)
+            .next_power_of_two()
+            .ilog2()
+    }
+
+    /// Number of trace columns, the ones constrained excluded.
+    pub fn n_inputs(&self) -> usize {
+        self.n_cols - self.n_constraints
+    }
+
+    /// Batch of each lookup.
+    pub fn batching(&self) -> Vec<usize> {
+        (0..self.n_lookups)
+            .map(|i| i / self.lookup_batch_size)
+            .collect()
+    }
+
+    /// Columns multiplied by the constraint of the `k`-th constrained column.
+    pub fn constraint_inputs(&self, k: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.constraint_degree).map(move |i| (k * self.constraint_degree + i) % self.n_inputs())
+    }
+
+    /// Column added to the relation by the `l`-th lookup.
+    pub fn lookup_column(&self, l: usize) -> usize {
+        l % self.n_cols
+    }
+
+    /// Ids of the preprocessed columns of the `index`-th component.
+    pub fn preprocessed_column_ids(&self, index: usize) -> Vec<PreProcessedColumnId> {
+        (0..self.n_preprocessed_cols)
+            .map(|i| PreProcessedColumnId {
+                id: format!("dummy_{index}_{i}"),
+            })
+            .collect()
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.log_size < LOG_N_LANES {
+            return Err(format!(
+                "Log size {} must be at least {LOG_N_LANES}",
+                self.log_size
+            ));
+        }
+        if self.n_constraints > 0 && self.n_constraints >= self.n_cols {
+            return Err(format!(
+                "{} constraints need more than {} columns",
+                self.n_constraints, self.n_cols
+            ));
+        }
+        if self.constraint_degree == 0 || self.lookup_batch_size == 0 {
+            return Err("Constraint degree and lookup batch size must be positive".to_string());
+        }
+        if self.n_lookups > 0 && self.n_cols == 0 {
+            return Err("Lookups need at least one column".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check that a PCS config of log blowup factor `log_blowup_factor` can prove the component.
+    pub fn check_log_blowup_factor(&self, log_blowup_factor: u32) -> Result<(), String> {
+        if log_blowup_factor < self.log_degree() {
+            return Err(format!(
+                "Log blowup factor {log_blowup_factor} must be at least the log degree {}",
+                self.log_degree()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct DummyEval {
+    pub config: DummyConfig,
+    /// Index of the component, naming its preprocessed columns.
+    pub index: usize,
+    pub relation: DummyRelation,
 }
 impl FrameworkEval for DummyEval {
     fn log_size(&self) -> u32 {
-        self.log_size
+        self.config.log_size
     }
     fn max_constraint_log_degree_bound(&self) -> u32 {
-        self.log_size + 1
+        self.config.log_size + self.config.log_degree()
     }
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        for _ in 0..self.n_cols {
-            eval.next_trace_mask();
+        let config = &self.config;
+        for id in config.preprocessed_column_ids(self.index) {
+            eval.get_preprocessed_column(id);
+        }
+        let cols: Vec<E::F> = (0..config.n_cols).map(|_| eval.next_trace_mask()).collect();
+
+        for (k, output) in cols[config.n_inputs()..].iter().enumerate() {
+            let product = config
+                .constraint_inputs(k)
+                .map(|i| cols[i].clone())
+                .reduce(|a, b| a * b)
+                .unwrap();
+            eval.add_constraint(output.clone() - product);
+        }
+
+        for l in 0..config.n_lookups {
+            eval.add_to_relation(RelationEntry::new(
+                &self.relation,
+                E::EF::one(),
+                &[cols[config.lookup_column(l)].clone()],
+            ));
+        }
+        if config.n_lookups > 0 {
+            eval.finalize_logup_batched(&config.batching());
         }
         eval
     }
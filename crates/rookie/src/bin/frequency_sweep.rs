@@ -0,0 +1,331 @@
+//! Sweep the parameters of the dummy prover and write a table of its frequency.
+//!
+//! ```bash
+//! RUSTFLAGS="-C target-cpu=native" cargo run -r --features peak-alloc --bin frequency-sweep -- \
+//!     --n-cols 4096,8192 --log-size 13,14 --output frequency.csv
+//! RUSTFLAGS="-C target-cpu=native" cargo run -r --features peak-alloc --bin frequency-sweep -- \
+//!     --log-blowup-factor 1,2,4 --n-queries 70,35,18 --channel blake2s,poseidon252 --format json
+//! ```
+//!
+//! Each flag takes a comma separated list of values and a row is written for each combination.
+//! A row runs `concurrency` proofs at once on a pool of `threads` threads, 0 for the default, and
+//! verifies one of them. The frequency is the number of trace cells, rows times columns, proven
+//! per second.
+//!
+//! The peak memory is measured by the global allocator of the `peak-alloc` feature, which the
+//! binary requires.
+
+use std::{fs, path::PathBuf, process::ExitCode, str::FromStr, time::Instant};
+
+use peak_alloc::PeakAlloc;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rookie::{
+    air::components::DummyConfig,
+    prover::{prove_dummy, verify_dummy},
+};
+use serde::Serialize;
+use stwo::{
+    core::{
+        channel::MerkleChannel,
+        pcs::PcsConfig,
+        vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+    },
+    prover::backend::{simd::SimdBackend, BackendForChannel},
+};
+
+#[global_allocator]
+static PEAK_ALLOC: PeakAlloc = PeakAlloc;
+
+const USAGE: &str = "Usage: frequency-sweep [--n-cols <n,..>] [--log-size <n,..>] \
+                     [--log-blowup-factor <n,..>] [--n-queries <n,..>] \
+                     [--channel <blake2s|poseidon252,..>] [--concurrency <n,..>] \
+                     [--threads <n,..>] [--format <csv|json>] [--output <file>]";
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Channel {
+    Blake2s,
+    Poseidon252,
+}
+
+impl FromStr for Channel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "blake2s" => Ok(Channel::Blake2s),
+            "poseidon252" => Ok(Channel::Poseidon252),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Channel::Blake2s => write!(f, "blake2s"),
+            Channel::Poseidon252 => write!(f, "poseidon252"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Args {
+    n_cols: Vec<usize>,
+    log_size: Vec<u32>,
+    log_blowup_factor: Vec<u32>,
+    n_queries: Vec<usize>,
+    channel: Vec<Channel>,
+    concurrency: Vec<usize>,
+    threads: Vec<usize>,
+    format: Format,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let config = PcsConfig::default();
+    let mut args = Args {
+        n_cols: vec![1 << 12, 1 << 13],
+        log_size: vec![13, 14],
+        log_blowup_factor: vec![config.fri_config.log_blowup_factor],
+        n_queries: vec![config.fri_config.n_queries],
+        channel: vec![Channel::Blake2s],
+        concurrency: vec![10],
+        threads: vec![0],
+        format: Format::Csv,
+        output: None,
+    };
+
+    fn parse_list<T: FromStr>(flag: &str, value: &str) -> Result<Vec<T>, String> {
+        value
+            .split(',')
+            .map(|item| {
+                item.trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid value for {flag}: {item}"))
+            })
+            .collect()
+    }
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(flag) = argv.next() {
+        let value = argv.next().ok_or(format!("Missing value for {flag}"))?;
+        match flag.as_str() {
+            "--n-cols" => args.n_cols = parse_list(&flag, &value)?,
+            "--log-size" => args.log_size = parse_list(&flag, &value)?,
+            "--log-blowup-factor" => args.log_blowup_factor = parse_list(&flag, &value)?,
+            "--n-queries" => args.n_queries = parse_list(&flag, &value)?,
+            "--channel" => args.channel = parse_list(&flag, &value)?,
+            "--concurrency" => args.concurrency = parse_list(&flag, &value)?,
+            "--threads" => args.threads = parse_list(&flag, &value)?,
+            "--format" => {
+                args.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err(format!("Unknown format: {value}")),
+                }
+            }
+            "--output" => args.output = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown argument: {flag}")),
+        }
+    }
+    if args.n_cols.contains(&0) || args.concurrency.contains(&0) {
+        return Err("Column count and concurrency must be at least 1".to_string());
+    }
+    // Fail before running any row on a component or PCS config the prover rejects
+    for &n_cols in &args.n_cols {
+        for &log_size in &args.log_size {
+            let config = DummyConfig::new(log_size, n_cols);
+            config.check()?;
+            for &log_blowup_factor in &args.log_blowup_factor {
+                config.check_log_blowup_factor(log_blowup_factor)?;
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// Parameters of a row of the sweep.
+#[derive(Clone, Copy, Debug, Serialize)]
+struct Params {
+    n_cols: usize,
+    log_size: u32,
+    log_blowup_factor: u32,
+    n_queries: usize,
+    channel: Channel,
+    concurrency: usize,
+    threads: usize,
+}
+
+impl Params {
+    fn pcs_config(&self) -> PcsConfig {
+        let mut config = PcsConfig::default();
+        config.fri_config.log_blowup_factor = self.log_blowup_factor;
+        config.fri_config.n_queries = self.n_queries;
+        config
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    #[serde(flatten)]
+    params: Params,
+    /// Threads of the pool the proofs ran on.
+    rayon_threads: usize,
+    /// Wall time of the `concurrency` proofs, in seconds.
+    prove_time: f64,
+    /// Trace cells proven per second.
+    hz: f64,
+    verify_time_ms: f64,
+    proof_size_bytes: u64,
+    peak_memory_bytes: usize,
+}
+
+const CSV_HEADER: &str = "n_cols,log_size,log_blowup_factor,n_queries,channel,concurrency,\
+                          threads,rayon_threads,prove_time,hz,verify_time_ms,proof_size_bytes,\
+                          peak_memory_bytes";
+
+impl Row {
+    fn to_csv(&self) -> String {
+        let p = &self.params;
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            p.n_cols,
+            p.log_size,
+            p.log_blowup_factor,
+            p.n_queries,
+            p.channel,
+            p.concurrency,
+            p.threads,
+            self.rayon_threads,
+            self.prove_time,
+            self.hz,
+            self.verify_time_ms,
+            self.proof_size_bytes,
+            self.peak_memory_bytes
+        )
+    }
+}
+
+/// Every combination of the values of the flags.
+fn sweep(args: &Args) -> Vec<Params> {
+    let mut params = vec![];
+    for &n_cols in &args.n_cols {
+        for &log_size in &args.log_size {
+            for &log_blowup_factor in &args.log_blowup_factor {
+                for &n_queries in &args.n_queries {
+                    for &channel in &args.channel {
+                        for &concurrency in &args.concurrency {
+                            for &threads in &args.threads {
+                                params.push(Params {
+                                    n_cols,
+                                    log_size,
+                                    log_blowup_factor,
+                                    n_queries,
+                                    channel,
+                                    concurrency,
+                                    threads,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    params
+}
+
+fn run<MC: MerkleChannel>(params: Params) -> Result<Row, String>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(params.threads)
+        .build()
+        .map_err(|err| err.to_string())?;
+    let configs = [DummyConfig::new(params.log_size, params.n_cols)];
+    let pcs_config = params.pcs_config();
+
+    pool.install(|| {
+        PEAK_ALLOC.reset_peak_usage();
+        let start = Instant::now();
+        let mut proofs = (0..params.concurrency)
+            .into_par_iter()
+            .map(|_| prove_dummy::<MC>(&configs, pcs_config))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+        let prove_time = start.elapsed().as_secs_f64();
+        let peak_memory_bytes = PEAK_ALLOC.peak_usage();
+
+        let proof = proofs.pop().unwrap();
+        drop(proofs);
+        let proof_size_bytes = proof.size_bytes();
+        let start = Instant::now();
+        verify_dummy::<MC>(&configs, pcs_config, proof).map_err(|err| err.to_string())?;
+        let verify_time_ms = start.elapsed().as_secs_f64() * 1e3;
+
+        let n_cells =
+            (params.concurrency * params.n_cols) as f64 * (1u64 << params.log_size) as f64;
+        Ok(Row {
+            params,
+            rayon_threads: rayon::current_num_threads(),
+            prove_time,
+            hz: n_cells / prove_time,
+            verify_time_ms,
+            proof_size_bytes,
+            peak_memory_bytes,
+        })
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut rows = vec![];
+    for params in sweep(&args) {
+        let row = match params.channel {
+            Channel::Blake2s => run::<Blake2sMerkleChannel>(params),
+            Channel::Poseidon252 => run::<Poseidon252MerkleChannel>(params),
+        };
+        match row {
+            Ok(row) => {
+                eprintln!("{}", row.to_csv());
+                rows.push(row);
+            }
+            Err(err) => {
+                eprintln!("{params:?}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let table = match args.format {
+        Format::Csv => std::iter::once(CSV_HEADER.to_string())
+            .chain(rows.iter().map(Row::to_csv))
+            .map(|line| line + "\n")
+            .collect::<String>(),
+        Format::Json => serde_json::to_string_pretty(&rows).unwrap(),
+    };
+    match args.output {
+        Some(path) => {
+            if let Err(err) = fs::write(&path, table) {
+                eprintln!("Could not write {}: {err}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{table}"),
+    }
+    ExitCode::SUCCESS
+}
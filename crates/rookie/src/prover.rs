@@ -1,36 +1,189 @@
-use num_traits::Zero;
+use num_traits::{One, Zero};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::Serialize;
 use stwo::{
     core::{
-        channel::MerkleChannel, fields::qm31::SecureField, pcs::PcsConfig,
-        poly::circle::CanonicCoset, proof::StarkProof,
+        air::Component,
+        channel::{Channel, MerkleChannel},
+        fields::{
+            m31::{BaseField, M31, P},
+            qm31::SecureField,
+        },
+        pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec},
+        poly::circle::CanonicCoset,
+        proof::StarkProof,
+        vcs::MerkleHasher,
+        verifier::{verify, VerificationError},
+        ColumnVec,
     },
     prover::{
-        backend::{simd::SimdBackend, BackendForChannel},
-        poly::circle::PolyOps,
-        prove, CommitmentSchemeProver, ProvingError as StwoProvingError,
+        backend::{
+            simd::{
+                column::BaseColumn,
+                m31::{PackedM31, LOG_N_LANES},
+                qm31::PackedQM31,
+                SimdBackend,
+            },
+            BackendForChannel,
+        },
+        poly::{
+            circle::{CircleEvaluation, PolyOps},
+            BitReversedOrder,
+        },
+        prove, CommitmentSchemeProver, ComponentProver, ProvingError as StwoProvingError,
     },
 };
-use stwo_constraint_framework::TraceLocationAllocator;
+use stwo_constraint_framework::{LogupTraceGenerator, Relation, TraceLocationAllocator};
+use thiserror::Error;
 use tracing::info;
-use utils::{circle_evaluation_u32x16, simd::generate_simd_sequence_bulk};
 
-use crate::air::components::{DummyComponent, DummyEval};
+use crate::air::components::{DummyComponent, DummyConfig, DummyEval, DummyRelation};
+
+type Evaluation = CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>;
+
+#[derive(Debug, Error)]
+pub enum DummyProverError {
+    #[error("Invalid component {config:?}: {reason}")]
+    InvalidConfig { config: DummyConfig, reason: String },
+    #[error("Proving error: {0}")]
+    Proving(#[from] StwoProvingError),
+}
+
+#[derive(Clone, Debug)]
+pub struct DummyProof<H: MerkleHasher> {
+    /// Logup sum of each component.
+    pub claimed_sums: Vec<SecureField>,
+    pub stark_proof: StarkProof<H>,
+}
+
+impl<H: MerkleHasher> DummyProof<H>
+where
+    StarkProof<H>: Serialize,
+{
+    /// Size of the proof serialized with bincode, in bytes.
+    pub fn size_bytes(&self) -> u64 {
+        bincode::serialized_size(&(&self.claimed_sums, &self.stark_proof)).unwrap()
+    }
+}
+
+/// Columns of `2^log_size` random values, from a seed per column.
+fn random_columns(log_size: u32, seeds: std::ops::Range<u64>) -> Vec<Vec<PackedM31>> {
+    seeds
+        .into_par_iter()
+        .map(|seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            (0..1 << (log_size - LOG_N_LANES))
+                .map(|_| {
+                    PackedM31::from_array(std::array::from_fn(|_| {
+                        M31::from_u32_unchecked(rng.gen_range(0..P))
+                    }))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn to_evaluation(log_size: u32, column: Vec<PackedM31>) -> Evaluation {
+    CircleEvaluation::new(
+        CanonicCoset::new(log_size).circle_domain(),
+        BaseColumn::from_simd(column),
+    )
+}
+
+/// Random preprocessed columns of the `index`-th component.
+fn gen_preprocessed_trace(config: &DummyConfig, index: usize) -> Vec<Evaluation> {
+    let seed = (index as u64) << 32;
+    random_columns(
+        config.log_size,
+        seed..seed + config.n_preprocessed_cols as u64,
+    )
+    .into_iter()
+    .map(|column| to_evaluation(config.log_size, column))
+    .collect()
+}
+
+/// Random trace of the `index`-th component, the constrained columns filled to satisfy their
+/// constraints.
+fn gen_trace(config: &DummyConfig, index: usize) -> Vec<Vec<PackedM31>> {
+    let seed = (index as u64) << 32 | 1 << 31;
+    let mut cols = random_columns(config.log_size, seed..seed + config.n_inputs() as u64);
+    for k in 0..config.n_constraints {
+        let output = (0..cols[0].len())
+            .map(|row| {
+                config
+                    .constraint_inputs(k)
+                    .map(|i| cols[i][row])
+                    .reduce(|a, b| a * b)
+                    .unwrap()
+            })
+            .collect();
+        cols.push(output);
+    }
+    cols
+}
+
+/// Logup columns of the lookups of a component, with their claimed sum.
+fn gen_interaction_trace(
+    config: &DummyConfig,
+    trace: &[Vec<PackedM31>],
+    relation: &DummyRelation,
+) -> (ColumnVec<Evaluation>, SecureField) {
+    if config.n_lookups == 0 {
+        return (vec![], SecureField::zero());
+    }
+    let mut interaction_trace = LogupTraceGenerator::new(config.log_size);
+    let lookups = (0..config.n_lookups).collect::<Vec<_>>();
+    for batch in lookups.chunks(config.lookup_batch_size) {
+        let mut col = interaction_trace.new_col();
+        for vec_row in 0..1 << (config.log_size - LOG_N_LANES) {
+            // Sum of the fractions 1 / combine(value) of the batch.
+            let (numerator, denominator) = batch
+                .iter()
+                .map(|&l| {
+                    let value = trace[config.lookup_column(l)][vec_row];
+                    let denominator: PackedQM31 = relation.combine(&[value]);
+                    (PackedQM31::one(), denominator)
+                })
+                .reduce(|(n_0, d_0), (n_1, d_1)| (n_0 * d_1 + n_1 * d_0, d_0 * d_1))
+                .unwrap();
+            col.write_frac(vec_row, numerator, denominator);
+        }
+        col.finalize_col();
+    }
+    interaction_trace.finalize_last()
+}
 
-pub fn prove_dummy<MC: MerkleChannel, const N: usize>(
-    log_size: u32,
-) -> Result<StarkProof<MC::H>, StwoProvingError>
+/// Prove random traces of the given component shapes.
+pub fn prove_dummy<MC: MerkleChannel>(
+    configs: &[DummyConfig],
+    pcs_config: PcsConfig,
+) -> Result<DummyProof<MC::H>, DummyProverError>
 where
     SimdBackend: BackendForChannel<MC>,
 {
+    for config in configs {
+        config
+            .check()
+            .and_then(|()| config.check_log_blowup_factor(pcs_config.fri_config.log_blowup_factor))
+            .map_err(|reason| DummyProverError::InvalidConfig {
+                config: config.clone(),
+                reason,
+            })?;
+    }
+
     // Setup protocol.
     let channel = &mut MC::C::default();
-
-    let pcs_config = PcsConfig::default();
     pcs_config.mix_into(channel);
 
     info!("twiddles");
+    let max_log_size = configs
+        .iter()
+        .map(|c| c.log_size)
+        .max()
+        .unwrap_or(LOG_N_LANES);
     let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_size + pcs_config.fri_config.log_blowup_factor + 2)
+        CanonicCoset::new(max_log_size + pcs_config.fri_config.log_blowup_factor + 2)
             .circle_domain()
             .half_coset,
     );
@@ -38,32 +191,162 @@ where
         CommitmentSchemeProver::<SimdBackend, MC>::new(pcs_config, &twiddles);
 
     // Preprocessed trace
+    info!("preprocessed trace");
     let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(vec![]);
+    tree_builder.extend_evals(
+        configs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, config)| gen_preprocessed_trace(config, index)),
+    );
     tree_builder.commit(channel);
 
     // Generate trace
     info!("trace");
-    let col = generate_simd_sequence_bulk(0, 1 << log_size);
-    let col = circle_evaluation_u32x16!(col);
-    let trace = vec![col; N];
-
+    let traces = configs
+        .iter()
+        .enumerate()
+        .map(|(index, config)| gen_trace(config, index))
+        .collect::<Vec<_>>();
     let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(trace);
+    tree_builder.extend_evals(configs.iter().zip(&traces).flat_map(|(config, trace)| {
+        trace
+            .iter()
+            .map(|column| to_evaluation(config.log_size, column.clone()))
+    }));
     tree_builder.commit(channel);
 
+    // Draw lookup elements.
+    let relation = DummyRelation::draw(channel);
+
+    // Interaction trace, only committed when there are lookups.
+    info!("interaction trace");
+    let (interaction_traces, claimed_sums): (Vec<_>, Vec<_>) = configs
+        .iter()
+        .zip(&traces)
+        .map(|(config, trace)| gen_interaction_trace(config, trace, &relation))
+        .unzip();
+    channel.mix_felts(&claimed_sums);
+    if configs.iter().any(|config| config.n_lookups > 0) {
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(interaction_traces.into_iter().flatten());
+        tree_builder.commit(channel);
+    }
+
     // Prove stark.
     info!("prove stark");
+    let components = dummy_components(configs, &relation, &claimed_sums);
+    for component in &components {
+        info!("Dummy component info:\n{}", component);
+    }
 
-    let component = DummyComponent::new(
-        &mut TraceLocationAllocator::default(),
-        DummyEval {
-            log_size,
-            n_cols: N,
-        },
-        SecureField::zero(),
+    let provers = components
+        .iter()
+        .map(|component| component as &dyn ComponentProver<SimdBackend>)
+        .collect::<Vec<_>>();
+    let stark_proof = prove::<SimdBackend, _>(&provers, channel, commitment_scheme)?;
+    Ok(DummyProof {
+        claimed_sums,
+        stark_proof,
+    })
+}
+
+/// Verify a proof of [`prove_dummy`] for the same component shapes and PCS config.
+///
+/// The lookups of the synthetic AIR have no counterpart, so the claimed sums are only mixed into
+/// the channel, not checked to cancel out.
+pub fn verify_dummy<MC: MerkleChannel>(
+    configs: &[DummyConfig],
+    pcs_config: PcsConfig,
+    proof: DummyProof<MC::H>,
+) -> Result<(), VerificationError> {
+    for config in configs {
+        config
+            .check()
+            .and_then(|()| config.check_log_blowup_factor(pcs_config.fri_config.log_blowup_factor))
+            .map_err(|err| {
+                VerificationError::InvalidStructure(format!("Invalid component {config:?}: {err}"))
+            })?;
+    }
+    // Preprocessed, trace, interaction if any lookup, and composition trees.
+    let has_lookups = configs.iter().any(|config| config.n_lookups > 0);
+    if proof.claimed_sums.len() != configs.len()
+        || proof.stark_proof.commitments.len() != 3 + has_lookups as usize
+    {
+        return Err(VerificationError::InvalidStructure(
+            "Proof does not match the components".to_string(),
+        ));
+    }
+
+    // Setup protocol.
+    let channel = &mut MC::C::default();
+    pcs_config.mix_into(channel);
+    let commitment_scheme = &mut CommitmentSchemeVerifier::<MC>::new(pcs_config);
+
+    // Column sizes do not depend on the lookup elements.
+    let log_sizes = TreeVec::concat_cols(
+        dummy_components(configs, &DummyRelation::dummy(), &proof.claimed_sums)
+            .iter()
+            .map(|component| component.trace_log_degree_bounds()),
+    );
+    let preprocessed_log_sizes = configs
+        .iter()
+        .flat_map(|config| vec![config.log_size; config.n_preprocessed_cols])
+        .collect::<Vec<_>>();
+
+    // Preprocessed trace.
+    commitment_scheme.commit(
+        proof.stark_proof.commitments[0],
+        &preprocessed_log_sizes,
+        channel,
     );
-    info!("Dummy component info:\n{}", component);
 
-    prove::<SimdBackend, _>(&[&component], channel, commitment_scheme)
+    // Trace.
+    commitment_scheme.commit(proof.stark_proof.commitments[1], &log_sizes[1], channel);
+
+    // Draw lookup elements.
+    let relation = DummyRelation::draw(channel);
+
+    // Interaction trace.
+    channel.mix_felts(&proof.claimed_sums);
+    if has_lookups {
+        commitment_scheme.commit(proof.stark_proof.commitments[2], &log_sizes[2], channel);
+    }
+
+    // Verify constraints.
+    let components = dummy_components(configs, &relation, &proof.claimed_sums);
+    let components = components
+        .iter()
+        .map(|component| component as &dyn Component)
+        .collect::<Vec<_>>();
+    verify(&components, channel, commitment_scheme, proof.stark_proof)
+}
+
+fn dummy_components(
+    configs: &[DummyConfig],
+    relation: &DummyRelation,
+    claimed_sums: &[SecureField],
+) -> Vec<DummyComponent> {
+    let preprocessed_ids = configs
+        .iter()
+        .enumerate()
+        .flat_map(|(index, config)| config.preprocessed_column_ids(index))
+        .collect::<Vec<_>>();
+    let allocator = &mut TraceLocationAllocator::new_with_preprocessed_columns(&preprocessed_ids);
+    configs
+        .iter()
+        .zip(claimed_sums)
+        .enumerate()
+        .map(|(index, (config, &claimed_sum))| {
+            DummyComponent::new(
+                allocator,
+                DummyEval {
+                    config: config.clone(),
+                    index,
+                    relation: relation.clone(),
+                },
+                claimed_sum,
+            )
+        })
+        .collect()
 }
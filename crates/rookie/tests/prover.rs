@@ -1,8 +1,106 @@
-use rookie::prover::prove_dummy;
-use stwo::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+use num_traits::One;
+use rookie::{
+    air::components::DummyConfig,
+    prover::{prove_dummy, verify_dummy, DummyProverError},
+};
+use stwo::core::{
+    fields::qm31::SecureField, pcs::PcsConfig, vcs::blake2_merkle::Blake2sMerkleChannel,
+    verifier::VerificationError,
+};
 
 #[test_log::test]
 fn test_prove_dummy() {
-    let result = prove_dummy::<Blake2sMerkleChannel, 10>(4);
-    assert!(result.is_ok());
+    let configs = [DummyConfig::new(4, 10)];
+    let proof = prove_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default()).unwrap();
+    verify_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default(), proof).unwrap();
+}
+
+#[test_log::test]
+fn test_prove_dummy_rejects_invalid_config() {
+    for config in [
+        DummyConfig::new(3, 10),
+        DummyConfig {
+            n_constraints: 10,
+            ..DummyConfig::new(4, 10)
+        },
+    ] {
+        let result = prove_dummy::<Blake2sMerkleChannel>(&[config.clone()], PcsConfig::default());
+        assert!(
+            matches!(result, Err(DummyProverError::InvalidConfig { config: c, .. }) if c == config)
+        );
+    }
+}
+
+#[test_log::test]
+fn test_prove_dummy_rejects_low_blowup_factor() {
+    let config = DummyConfig {
+        n_constraints: 1,
+        constraint_degree: 4,
+        ..DummyConfig::new(4, 10)
+    };
+    let mut pcs_config = PcsConfig::default();
+    pcs_config.fri_config.log_blowup_factor = config.log_degree() - 1;
+
+    let result = prove_dummy::<Blake2sMerkleChannel>(&[config.clone()], pcs_config);
+    assert!(
+        matches!(result, Err(DummyProverError::InvalidConfig { config: c, .. }) if c == config)
+    );
+
+    pcs_config.fri_config.log_blowup_factor = config.log_degree();
+    let proof = prove_dummy::<Blake2sMerkleChannel>(&[config.clone()], pcs_config).unwrap();
+    pcs_config.fri_config.log_blowup_factor = config.log_degree() - 1;
+    assert!(matches!(
+        verify_dummy::<Blake2sMerkleChannel>(&[config], pcs_config, proof),
+        Err(VerificationError::InvalidStructure(_))
+    ));
+}
+
+#[test_log::test]
+fn test_prove_dummy_constraints_and_lookups() {
+    let configs = [
+        DummyConfig {
+            n_constraints: 3,
+            constraint_degree: 4,
+            n_lookups: 5,
+            lookup_batch_size: 2,
+            n_preprocessed_cols: 2,
+            ..DummyConfig::new(5, 10)
+        },
+        DummyConfig {
+            n_lookups: 1,
+            ..DummyConfig::new(4, 3)
+        },
+    ];
+    let mut pcs_config = PcsConfig::default();
+    pcs_config.fri_config.log_blowup_factor = configs.iter().map(|c| c.log_degree()).max().unwrap();
+
+    let proof = prove_dummy::<Blake2sMerkleChannel>(&configs, pcs_config).unwrap();
+    assert_eq!(proof.claimed_sums.len(), configs.len());
+    verify_dummy::<Blake2sMerkleChannel>(&configs, pcs_config, proof).unwrap();
+}
+
+#[test_log::test]
+fn test_verify_dummy_rejects_other_config() {
+    let configs = [DummyConfig {
+        n_lookups: 2,
+        ..DummyConfig::new(4, 4)
+    }];
+    let proof = prove_dummy::<Blake2sMerkleChannel>(&configs, PcsConfig::default()).unwrap();
+
+    let mut claimed_sums_proof = proof.clone();
+    claimed_sums_proof.claimed_sums[0] += SecureField::one();
+    assert!(verify_dummy::<Blake2sMerkleChannel>(
+        &configs,
+        PcsConfig::default(),
+        claimed_sums_proof
+    )
+    .is_err());
+
+    let other_configs = [DummyConfig {
+        n_lookups: 2,
+        ..DummyConfig::new(5, 4)
+    }];
+    assert!(
+        verify_dummy::<Blake2sMerkleChannel>(&other_configs, PcsConfig::default(), proof).is_err()
+    );
 }
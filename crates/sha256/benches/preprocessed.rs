@@ -0,0 +1,149 @@
+//! Lookup multiplicity counting of the preprocessed components, on a single thread against the
+//! whole rayon pool.
+//!
+//! The serial counting used before [`par_histograms`] is kept as the baseline, on the `BigSigma0`
+//! I0 and I1 lookups of the compression rounds.
+//!
+//! The lookup data of the largest sizes takes tens of GB, run them on a machine that fits it.
+
+#![feature(portable_simd)]
+
+use std::simd::u32x16;
+
+use itertools::izip;
+use rayon::ThreadPoolBuilder;
+use sha256::{
+    components::{
+        compression,
+        preprocessed::{self, lookup::compression_rounds},
+        scheduling,
+    },
+    partitions::{pext_u32x16, BigSigma0},
+};
+use utils::{aligned_vec, simd::par_histograms};
+
+fn main() {
+    divan::main();
+}
+
+/// Number of threads counting the multiplicities, 0 for one per core.
+const N_THREADS: &[usize] = &[1, 0];
+
+/// Log sizes of the benches.
+const LOG_SIZES: &[u32] = &[13, 14, 15, 16, 17, 18, 19, 20];
+
+fn gen_lookup_data(log_size: u32) -> (Vec<Vec<u32x16>>, Vec<Vec<u32x16>>) {
+    let (scheduling_trace, scheduling_lookup_data) = scheduling::witness::gen_trace(log_size);
+    let (_, compression_lookup_data) = compression::witness::gen_trace(&scheduling_trace);
+    (scheduling_lookup_data, compression_lookup_data)
+}
+
+/// Serial counting of the `BigSigma0` I0 and I1 lookups, in dense counters over all the rows.
+fn serial_big_sigma_0_histograms(compression_lookup_data: &[Vec<u32x16>]) -> [Vec<u32>; 2] {
+    let mut i0_mult = aligned_vec![0u32; 1 << BigSigma0::I0.count_ones()];
+    let mut i1_mult = aligned_vec![0u32; 1 << BigSigma0::I1.count_ones()];
+
+    // Aggregate over all compression lookups
+    for cols in compression_rounds(compression_lookup_data) {
+        izip!(cols.a_i0_low, cols.a_i0_high_0, cols.a_i0_high_1).for_each(
+            |(a_i0_low, a_i0_high_0, a_i0_high_1)| {
+                let idx_i0 = pext_u32x16(
+                    a_i0_low + (a_i0_high_0 << 16) + (a_i0_high_1 << 24),
+                    BigSigma0::I0,
+                );
+                idx_i0
+                    .to_array()
+                    .iter()
+                    .for_each(|x| i0_mult[*x as usize] += 1);
+            },
+        );
+        izip!(cols.a_i1_low_0, cols.a_i1_low_1, cols.a_i1_high).for_each(
+            |(a_i1_low_0, a_i1_low_1, a_i1_high)| {
+                let idx_i1 = pext_u32x16(
+                    a_i1_low_0 + (a_i1_low_1 << 8) + (a_i1_high << 16),
+                    BigSigma0::I1,
+                );
+                idx_i1
+                    .to_array()
+                    .iter()
+                    .for_each(|x| i1_mult[*x as usize] += 1);
+            },
+        );
+    }
+
+    [i0_mult, i1_mult]
+}
+
+/// The same counting as [`serial_big_sigma_0_histograms`] with [`par_histograms`].
+fn par_big_sigma_0_histograms(compression_lookup_data: &[Vec<u32x16>]) -> [Vec<u32>; 2] {
+    par_histograms(
+        [
+            1 << BigSigma0::I0.count_ones(),
+            1 << BigSigma0::I1.count_ones(),
+        ],
+        compression_lookup_data[0].len(),
+        |rows, [i0_mult, i1_mult]| {
+            for cols in compression_rounds(compression_lookup_data) {
+                for row in rows.clone() {
+                    let idx_i0 = pext_u32x16(
+                        cols.a_i0_low[row]
+                            + (cols.a_i0_high_0[row] << 16)
+                            + (cols.a_i0_high_1[row] << 24),
+                        BigSigma0::I0,
+                    );
+                    idx_i0
+                        .to_array()
+                        .iter()
+                        .for_each(|x| i0_mult[*x as usize] += 1);
+                    let idx_i1 = pext_u32x16(
+                        cols.a_i1_low_0[row]
+                            + (cols.a_i1_low_1[row] << 8)
+                            + (cols.a_i1_high[row] << 16),
+                        BigSigma0::I1,
+                    );
+                    idx_i1
+                        .to_array()
+                        .iter()
+                        .for_each(|x| i1_mult[*x as usize] += 1);
+                }
+            }
+        },
+    )
+}
+
+#[divan::bench(args = LOG_SIZES, sample_count = 1)]
+fn bench_serial_histograms(bencher: divan::Bencher, log_size: u32) {
+    let (_, compression_lookup_data) = gen_lookup_data(log_size);
+
+    bencher.bench_local(|| serial_big_sigma_0_histograms(&compression_lookup_data));
+}
+
+#[divan::bench(consts = N_THREADS, args = LOG_SIZES, sample_count = 1)]
+fn bench_par_histograms<const N_THREADS: usize>(bencher: divan::Bencher, log_size: u32) {
+    let (_, compression_lookup_data) = gen_lookup_data(log_size);
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(N_THREADS)
+        .build()
+        .unwrap();
+    assert_eq!(
+        pool.install(|| par_big_sigma_0_histograms(&compression_lookup_data)),
+        serial_big_sigma_0_histograms(&compression_lookup_data)
+    );
+
+    bencher.bench_local(|| pool.install(|| par_big_sigma_0_histograms(&compression_lookup_data)));
+}
+
+#[divan::bench(consts = N_THREADS, args = LOG_SIZES, sample_count = 1)]
+fn bench_preprocessed_gen_trace<const N_THREADS: usize>(bencher: divan::Bencher, log_size: u32) {
+    let (scheduling_lookup_data, compression_lookup_data) = gen_lookup_data(log_size);
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(N_THREADS)
+        .build()
+        .unwrap();
+
+    bencher.bench_local(|| {
+        pool.install(|| {
+            preprocessed::gen_trace(log_size, &scheduling_lookup_data, &compression_lookup_data)
+        })
+    });
+}
@@ -1,6 +1,13 @@
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use sha256::{print_enabled_features, prove_sha256};
-use stwo::core::pcs::PcsConfig;
+use sha256::{print_enabled_features, Sha256Prover};
+use stwo::{
+    core::{
+        channel::MerkleChannel,
+        pcs::PcsConfig,
+        vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+    },
+    prover::backend::{simd::SimdBackend, BackendForChannel},
+};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 fn main() {
@@ -14,19 +21,24 @@ fn main() {
 const N_ITER: &[usize] = &[6, 7, 8];
 
 #[divan::bench(
+    types = [Blake2sMerkleChannel, Poseidon252MerkleChannel],
     consts = N_ITER,
     args = [13, 14],
     sample_count = 1
 )]
-fn bench_sha256<const N_ITER: usize>(bencher: divan::Bencher, log_size: u32) {
+fn bench_sha256<MC: MerkleChannel, const N_ITER: usize>(bencher: divan::Bencher, log_size: u32)
+where
+    SimdBackend: BackendForChannel<MC>,
+{
     print_enabled_features();
+    let prover = Sha256Prover::<MC>::new(log_size, PcsConfig::default()).unwrap();
 
     bencher.bench(|| {
         #[cfg(feature = "peak-alloc")]
         PEAK_ALLOC.reset_peak_usage();
         (0..N_ITER)
             .into_par_iter()
-            .map(|_| prove_sha256(log_size, PcsConfig::default()))
+            .map(|_| prover.prove().unwrap())
             .collect::<Vec<_>>();
         #[cfg(feature = "peak-alloc")]
         {
@@ -0,0 +1,56 @@
+//! Print the columns, constraints and relation uses of each component as JSON.
+//!
+//! ```bash
+//! cargo run -r --bin air_stats -- --log-size 16
+//! cargo run -r --bin air_stats -- --log-size 20 --public-blocks
+//! ```
+
+use std::process::ExitCode;
+
+use sha256::{max_log_size, stats::air_stats};
+use stwo::{core::pcs::PcsConfig, prover::backend::simd::m31::LOG_N_LANES};
+
+const USAGE: &str = "Usage: air_stats [--log-size <n>] [--public-blocks]";
+
+const DEFAULT_LOG_SIZE: u32 = 16;
+
+fn parse_args() -> Result<(u32, bool), String> {
+    let mut log_size = DEFAULT_LOG_SIZE;
+    let mut public_blocks = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--log-size" => {
+                let value = args.next().ok_or(format!("Missing value for {flag}"))?;
+                log_size = value
+                    .parse()
+                    .map_err(|_| format!("Invalid log size: {value}"))?;
+                // The same bounds as the prover, with the default PCS config
+                let max = max_log_size(PcsConfig::default());
+                if !(LOG_N_LANES..=max).contains(&log_size) {
+                    return Err(format!(
+                        "Invalid log size {log_size}, expected between {LOG_N_LANES} and {max}"
+                    ));
+                }
+            }
+            "--public-blocks" => public_blocks = true,
+            _ => return Err(format!("Unknown argument: {flag}")),
+        }
+    }
+    Ok((log_size, public_blocks))
+}
+
+fn main() -> ExitCode {
+    let (log_size, public_blocks) = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stats = air_stats(log_size, public_blocks);
+    println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,132 @@
+//! Search I0/I1 partition masks and print them as a module of `partitions.rs`.
+//!
+//! ```bash
+//! cargo run -r --bin search_masks -- --preset BigSigma1
+//! cargo run -r --bin search_masks -- --rotations 6,11,25 --arities 2,2 --max-log-size 18
+//! ```
+//!
+//! The module derives every mask from I0. For a preset, or with `--sigma <path>`, it also asserts
+//! at compile time that the rotation and shift amounts match the sigma function at that path.
+
+use std::process::ExitCode;
+
+use sha256::{
+    mask_search::{search, sha256_presets, SearchParams, SigmaSpec, Split},
+    partitions::MAX_TABLE_LOG_SIZE,
+};
+
+const USAGE: &str = "Usage: search_masks [--preset <Sigma0|Sigma1|BigSigma0|BigSigma1>] \
+                     [--name <module>] [--sigma <path>] [--rotations <r,..>] [--shift <s>] \
+                     [--arities <a,..>] [--max-log-size <n>]";
+
+fn parse_list(value: &str) -> Result<Vec<u32>, String> {
+    value
+        .split(',')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().map_err(|_| format!("Invalid number: {v}")))
+        .collect()
+}
+
+struct Args {
+    params: SearchParams,
+    name: String,
+    /// Path of the sigma function.
+    sigma: Option<String>,
+    /// Current I0 mask of a preset.
+    current: Option<u32>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut params = SearchParams {
+        spec: SigmaSpec {
+            rotations: vec![],
+            shift: None,
+        },
+        arities: vec![],
+        max_log_size: MAX_TABLE_LOG_SIZE,
+    };
+    let mut name = "Sigma".to_string();
+    let mut sigma = None;
+    let mut current = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(format!("Missing value for {flag}"))?;
+        match flag.as_str() {
+            "--preset" => {
+                let (preset, preset_params, i0, path) = sha256_presets()
+                    .into_iter()
+                    .find(|(preset, ..)| *preset == value)
+                    .ok_or(format!("Unknown preset: {value}"))?;
+                params = preset_params;
+                name = preset.to_string();
+                sigma = Some(path.to_string());
+                current = Some(i0);
+            }
+            "--name" => name = value,
+            "--sigma" => sigma = Some(value),
+            "--rotations" => params.spec.rotations = parse_list(&value)?,
+            "--shift" => {
+                params.spec.shift = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&shift| shift < u32::BITS)
+                        .ok_or(format!("Invalid shift: {value}, expected below 32"))?,
+                )
+            }
+            "--arities" => params.arities = parse_list(&value)?,
+            "--max-log-size" => {
+                params.max_log_size = value
+                    .parse()
+                    .map_err(|_| format!("Invalid max log size: {value}"))?
+            }
+            _ => return Err(format!("Unknown argument: {flag}")),
+        }
+    }
+    if params.spec.rotations.is_empty() {
+        return Err("Missing --rotations or --preset".to_string());
+    }
+    Ok(Args {
+        params,
+        name,
+        sigma,
+        current,
+    })
+}
+
+fn print_cost(label: &str, split: &Split) {
+    println!(
+        "// {label}: {} rows, {} columns",
+        split.cost.rows, split.cost.columns
+    );
+}
+
+fn main() -> ExitCode {
+    let Args {
+        params,
+        name,
+        sigma,
+        current,
+    } = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(i0) = current {
+        print_cost("Current", &Split::new(&params, i0));
+    }
+    let Some(split) = search(&params) else {
+        eprintln!(
+            "No split with tables of log size at most {}",
+            params.max_log_size
+        );
+        return ExitCode::FAILURE;
+    };
+    print_cost("Found", &split);
+    println!("{}", split.to_rust(&name, &params.spec, sigma.as_deref()));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,341 @@
+//! Benchmark the SHA-256 prover and write the results as a JSON record.
+//!
+//! ```bash
+//! RUSTFLAGS="-C target-cpu=native" cargo run -r --bin sha256-bench -- \
+//!     --log-size 16 --concurrency 4
+//! RUSTFLAGS="-C target-cpu=native" cargo run -r --bin sha256-bench -- \
+//!     --log-size 14 --channel poseidon252 --log-blowup-factor 2 --output results.json
+//! ```
+//!
+//! `--concurrency` proofs of `2^log_size` synthetic blocks run at once on a session shared by all
+//! of them. The times of the phases of the proofs are averaged over the proofs.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::Serialize;
+use sha256::{enabled_features, peak_memory_bytes, Sha256Prover, Sha256ProverError};
+use stwo::{
+    core::{
+        channel::MerkleChannel,
+        pcs::PcsConfig,
+        vcs::{blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel},
+    },
+    prover::backend::{simd::SimdBackend, BackendForChannel},
+};
+use tracing::{span, Subscriber};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+const USAGE: &str = "Usage: sha256-bench [--log-size <n>] [--concurrency <n>] \
+                     [--channel <blake2s|poseidon252>] [--log-blowup-factor <n>] \
+                     [--n-queries <n>] [--pow-bits <n>] [--output <file>]";
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Channel {
+    Blake2s,
+    Poseidon252,
+}
+
+#[derive(Debug, Serialize)]
+struct Args {
+    log_size: u32,
+    concurrency: usize,
+    channel: Channel,
+    log_blowup_factor: u32,
+    n_queries: usize,
+    pow_bits: u32,
+    #[serde(skip)]
+    output: Option<PathBuf>,
+}
+
+impl Args {
+    fn config(&self) -> PcsConfig {
+        let mut config = PcsConfig::default();
+        config.pow_bits = self.pow_bits;
+        config.fri_config.log_blowup_factor = self.log_blowup_factor;
+        config.fri_config.n_queries = self.n_queries;
+        config
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let config = PcsConfig::default();
+    let mut args = Args {
+        log_size: 13,
+        concurrency: 1,
+        channel: Channel::Blake2s,
+        log_blowup_factor: config.fri_config.log_blowup_factor,
+        n_queries: config.fri_config.n_queries,
+        pow_bits: config.pow_bits,
+        output: None,
+    };
+
+    fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+        value
+            .parse()
+            .map_err(|_| format!("Invalid value for {flag}: {value}"))
+    }
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(flag) = argv.next() {
+        let value = argv.next().ok_or(format!("Missing value for {flag}"))?;
+        match flag.as_str() {
+            "--log-size" => args.log_size = parse(&flag, &value)?,
+            "--concurrency" => args.concurrency = parse(&flag, &value)?,
+            "--channel" => {
+                args.channel = match value.as_str() {
+                    "blake2s" => Channel::Blake2s,
+                    "poseidon252" => Channel::Poseidon252,
+                    _ => return Err(format!("Unknown channel: {value}")),
+                }
+            }
+            "--log-blowup-factor" => args.log_blowup_factor = parse(&flag, &value)?,
+            "--n-queries" => args.n_queries = parse(&flag, &value)?,
+            "--pow-bits" => args.pow_bits = parse(&flag, &value)?,
+            "--output" => args.output = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown argument: {flag}")),
+        }
+    }
+    if args.concurrency == 0 {
+        return Err("Concurrency must be at least 1".to_string());
+    }
+    // The rayon workers are charged to the span entered last, which mixes up concurrent proofs
+    if cfg!(feature = "span-alloc") && args.concurrency > 1 {
+        return Err("The span-alloc feature needs a concurrency of 1".to_string());
+    }
+    Ok(args)
+}
+
+/// Wall time of the spans without parent, summed by name.
+#[derive(Clone, Default)]
+struct SpanTimes(Arc<Mutex<HashMap<&'static str, Duration>>>);
+
+struct SpanStart(Instant);
+
+impl SpanTimes {
+    /// Milliseconds spent in the span `name`, divided by `n`.
+    fn mean_ms(&self, name: &str, n: usize) -> f64 {
+        let times = self.0.lock().unwrap();
+        times
+            .get(name)
+            .map_or(0.0, |d| d.as_secs_f64() * 1e3 / n as f64)
+    }
+
+    fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanTimes {
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        if span.parent().is_some() {
+            return;
+        }
+        if let Some(SpanStart(start)) = span.extensions().get::<SpanStart>() {
+            *self.0.lock().unwrap().entry(span.name()).or_default() += start.elapsed();
+        }
+    }
+}
+
+/// Mean wall time of each phase, in milliseconds.
+#[derive(Debug, Serialize)]
+struct PhaseTimes {
+    twiddles: f64,
+    /// Generation and commitment of the preprocessed trace, once per session.
+    preprocessed: f64,
+    /// Reuse of the committed preprocessed trace, in each proof.
+    commit_preprocessed: f64,
+    trace: f64,
+    interaction: f64,
+    prove: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Machine {
+    os: &'static str,
+    arch: &'static str,
+    cpu: Option<String>,
+    available_parallelism: Option<usize>,
+    rayon_threads: usize,
+    features: Vec<&'static str>,
+}
+
+impl Machine {
+    fn new() -> Self {
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpu: cpu_model(),
+            available_parallelism: std::thread::available_parallelism().ok().map(|n| n.get()),
+            rayon_threads: rayon::current_num_threads(),
+            features: enabled_features(),
+        }
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
+        return cpuinfo
+            .lines()
+            .find_map(|line| line.strip_prefix("model name"))
+            .and_then(|line| line.split_once(':'))
+            .map(|(_, model)| model.trim().to_string());
+    }
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "machdep.cpu.brand_string"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct Record {
+    timestamp: u64,
+    args: Args,
+    n_hashes: u64,
+    /// Wall time of the proofs, without the session setup, in seconds.
+    wall_time: f64,
+    hashes_per_sec: f64,
+    phases_ms: PhaseTimes,
+    peak_memory_bytes: Option<u64>,
+    /// Memory charged to each span, with the `span-alloc` feature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_memory: Option<Vec<SpanMemory>>,
+    machine: Machine,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(not(feature = "span-alloc"), allow(dead_code))]
+struct SpanMemory {
+    span: String,
+    allocated_bytes: u64,
+    peak_bytes: u64,
+}
+
+fn span_memory() -> Option<Vec<SpanMemory>> {
+    #[cfg(feature = "span-alloc")]
+    {
+        Some(
+            utils::alloc::report()
+                .into_iter()
+                .map(|usage| SpanMemory {
+                    span: usage.span,
+                    allocated_bytes: usage.allocated,
+                    peak_bytes: usage.peak,
+                })
+                .collect(),
+        )
+    }
+    #[cfg(not(feature = "span-alloc"))]
+    {
+        None
+    }
+}
+
+fn bench<MC: MerkleChannel>(args: Args, times: &SpanTimes) -> Result<Record, Sha256ProverError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let prover = Sha256Prover::<MC>::new(args.log_size, args.config())?;
+    let twiddles = times.mean_ms("Precompute twiddles", 1);
+    let preprocessed = times.mean_ms("Constant", 1);
+    times.clear();
+
+    let start = Instant::now();
+    (0..args.concurrency)
+        .into_par_iter()
+        .map(|_| prover.prove().map(|_| ()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let wall_time = start.elapsed().as_secs_f64();
+
+    let n = args.concurrency;
+    let n_hashes = (n as u64) << args.log_size;
+    Ok(Record {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        args,
+        n_hashes,
+        wall_time,
+        hashes_per_sec: n_hashes as f64 / wall_time,
+        phases_ms: PhaseTimes {
+            twiddles,
+            preprocessed,
+            commit_preprocessed: times.mean_ms("Constant", n),
+            trace: times.mean_ms("Trace", n),
+            interaction: times.mean_ms("Interaction", n),
+            prove: times.mean_ms("Prove", n),
+        },
+        peak_memory_bytes: peak_memory_bytes(),
+        span_memory: span_memory(),
+        machine: Machine::new(),
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let times = SpanTimes::default();
+    let registry = tracing_subscriber::registry()
+        .with(times.clone())
+        .with(tracing_subscriber::fmt::layer().with_filter(EnvFilter::from_default_env()));
+    #[cfg(feature = "span-alloc")]
+    let registry = registry.with(utils::alloc::SpanAllocLayer::new(sha256::ALLOC_SPANS));
+    registry.init();
+
+    let output = args.output.clone();
+    let record = match args.channel {
+        Channel::Blake2s => bench::<Blake2sMerkleChannel>(args, &times),
+        Channel::Poseidon252 => bench::<Poseidon252MerkleChannel>(args, &times),
+    };
+    let record = match record {
+        Ok(record) => record,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&record).unwrap();
+    match output {
+        Some(path) => {
+            if let Err(err) = fs::write(&path, json) {
+                eprintln!("Could not write {}: {err}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => println!("{json}"),
+    }
+    ExitCode::SUCCESS
+}
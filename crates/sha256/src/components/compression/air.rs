@@ -4,15 +4,23 @@ use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval};
 use utils::add_to_relation;
 
 use crate::{
-    components::{compression::columns::RoundColumnsOwned, W_SIZE},
+    components::{
+        compression::columns::{ChainColumnsOwned, FeedForwardColumnsOwned, RoundColumnsOwned},
+        W_SIZE,
+    },
     relations::Relations,
-    sha256::{H, K, N_COMPRESSION_ROUNDS},
+    sha256::{CHUNK_SIZE, H, K, N_COMPRESSION_ROUNDS},
 };
 
 pub type Component = FrameworkComponent<Eval>;
 
-fn eval_compression_constraints<E: EvalAtRow>(eval: &mut E, relations: &Relations) {
+fn eval_compression_constraints<E: EvalAtRow>(
+    eval: &mut E,
+    relations: &Relations,
+    public_blocks: bool,
+) {
     let w: [E::F; W_SIZE] = std::array::from_fn(|_| eval.next_trace_mask());
+    let chain = ChainColumnsOwned::<<E as EvalAtRow>::F>::from_eval(eval);
 
     let k: [E::F; K.len() * 2] = K
         .iter()
@@ -26,18 +34,28 @@ fn eval_compression_constraints<E: EvalAtRow>(eval: &mut E, relations: &Relation
         .try_into()
         .unwrap();
 
-    let mut hash_buffer: [E::F; H.len() * 2] = H
-        .iter()
-        .flat_map(|h| {
-            [
-                E::F::from(M31::from(h & 0xffff)),
-                E::F::from(M31::from(h >> 16)),
-            ]
-        })
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
+    // Start from the state left by the previous block
+    let state: [E::F; H.len() * 2] = [
+        chain.a_low.clone(),
+        chain.a_high.clone(),
+        chain.b_low.clone(),
+        chain.b_high.clone(),
+        chain.c_low.clone(),
+        chain.c_high.clone(),
+        chain.d_low.clone(),
+        chain.d_high.clone(),
+        chain.e_low.clone(),
+        chain.e_high.clone(),
+        chain.f_low.clone(),
+        chain.f_high.clone(),
+        chain.g_low.clone(),
+        chain.g_high.clone(),
+        chain.h_low.clone(),
+        chain.h_high.clone(),
+    ];
+    let mut hash_buffer = state.clone();
 
+    let one = E::EF::one();
     let minus_one = -E::EF::one();
     for round in 0..N_COMPRESSION_ROUNDS {
         let a_low = hash_buffer[0].clone();
@@ -364,6 +382,77 @@ fn eval_compression_constraints<E: EvalAtRow>(eval: &mut E, relations: &Relation
         hash_buffer[15] = g_high.clone(); // h_high
     }
 
+    // Feed-forward: out = state + hash_buffer
+    let mut out: Vec<E::F> = Vec::with_capacity(H.len() * 2);
+    for i in 0..H.len() {
+        let cols = FeedForwardColumnsOwned::<<E as EvalAtRow>::F>::from_eval(eval);
+        out.push(cols.out_low.clone());
+        out.push(cols.out_high.clone());
+
+        eval.add_constraint(
+            cols.out_low.clone() + cols.carry_low.clone() * E::F::from(M31::from(1 << 16))
+                - hash_buffer[2 * i].clone()
+                - state[2 * i].clone(),
+        );
+        eval.add_constraint(
+            cols.out_high.clone() + cols.carry_high.clone() * E::F::from(M31::from(1 << 16))
+                - hash_buffer[2 * i + 1].clone()
+                - state[2 * i + 1].clone()
+                - cols.carry_low.clone(),
+        );
+
+        add_to_relation!(
+            eval,
+            relations.range_check_add.add_4,
+            minus_one,
+            cols.out_low,
+            cols.carry_low
+        );
+        add_to_relation!(
+            eval,
+            relations.range_check_add.add_4,
+            minus_one,
+            cols.out_high,
+            cols.carry_high
+        );
+    }
+
+    // Consume the input state and emit the output state for the next block
+    let input = [chain.msg_id.clone(), chain.block_idx.clone()]
+        .into_iter()
+        .chain(state)
+        .collect::<Vec<_>>();
+    let output = [
+        chain.msg_id.clone(),
+        chain.block_idx.clone() + E::F::from(M31::from(1)),
+    ]
+    .into_iter()
+    .chain(out)
+    .collect::<Vec<_>>();
+    eval.add_to_relation(stwo_constraint_framework::RelationEntry::new(
+        &relations.chain,
+        minus_one.clone(),
+        &input,
+    ));
+    eval.add_to_relation(stwo_constraint_framework::RelationEntry::new(
+        &relations.chain,
+        one.clone(),
+        &output,
+    ));
+
+    // Emit the message block consumed by the verifier
+    if public_blocks {
+        let block = [chain.msg_id, chain.block_idx]
+            .into_iter()
+            .chain(w[..CHUNK_SIZE].iter().cloned())
+            .collect::<Vec<_>>();
+        eval.add_to_relation(stwo_constraint_framework::RelationEntry::new(
+            &relations.message,
+            one,
+            &block,
+        ));
+    }
+
     // Consume W emitted by scheduling
     eval.add_to_relation(stwo_constraint_framework::RelationEntry::new(
         &relations.w,
@@ -378,6 +467,8 @@ fn eval_compression_constraints<E: EvalAtRow>(eval: &mut E, relations: &Relation
 pub struct Eval {
     pub log_size: u32,
     pub relations: Relations,
+    /// Whether the message blocks are part of the public statement
+    pub public_blocks: bool,
 }
 impl FrameworkEval for Eval {
     fn log_size(&self) -> u32 {
@@ -387,7 +478,7 @@ impl FrameworkEval for Eval {
         self.log_size() + 1
     }
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        eval_compression_constraints(&mut eval, &self.relations);
+        eval_compression_constraints(&mut eval, &self.relations, self.public_blocks);
         eval
     }
 }
@@ -399,21 +490,33 @@ mod tests {
     use stwo_constraint_framework::assert_constraints_on_polys;
 
     use super::*;
-    use crate::components::{
-        compression::witness::{gen_interaction_trace, gen_trace},
-        scheduling::witness::gen_trace as gen_scheduling_trace,
+    use crate::{
+        components::{
+            compression::{
+                columns::{ChainColumns, FeedForwardColumns, RoundColumns},
+                witness::{gen_interaction_trace, gen_trace_chained},
+            },
+            mutation::{self, MutationHarness},
+            scheduling::witness::{
+                gen_blocks, gen_trace_from_blocks as gen_scheduling_trace_from_blocks,
+            },
+        },
+        messages::{pad_message, Rows},
     };
 
-    #[test]
-    fn test_compression_constraints() {
-        const LOG_N_ROWS: u32 = 4;
-
+    fn assert_compression_constraints(log_n_rows: u32, rows: &Rows, public_blocks: bool) {
         // Trace.
-        let (scheduling_trace, _) = gen_scheduling_trace(LOG_N_ROWS);
-        let (trace, lookup_data) = gen_trace(&scheduling_trace);
+        let (scheduling_trace, _) = gen_scheduling_trace_from_blocks(&rows.blocks);
+        let (trace, lookup_data) = gen_trace_chained(
+            &scheduling_trace,
+            &rows.msg_ids,
+            &rows.block_indices,
+            &rows.states,
+        );
 
         let relations = Relations::dummy();
-        let (interaction_trace, claimed_sum) = gen_interaction_trace(&lookup_data, &relations);
+        let (interaction_trace, claimed_sum) =
+            gen_interaction_trace(&lookup_data, &relations, public_blocks);
 
         let traces = TreeVec::new(vec![vec![], trace, interaction_trace]);
         let trace_polys =
@@ -421,11 +524,70 @@ mod tests {
 
         assert_constraints_on_polys(
             &trace_polys,
-            CanonicCoset::new(LOG_N_ROWS),
+            CanonicCoset::new(log_n_rows),
             |mut eval| {
-                eval_compression_constraints(&mut eval, &relations);
+                eval_compression_constraints(&mut eval, &relations, public_blocks);
             },
             claimed_sum,
         );
     }
+
+    #[test]
+    fn test_compression_constraints() {
+        const LOG_N_ROWS: u32 = 4;
+        let rows = Rows::from_blocks(&gen_blocks(LOG_N_ROWS));
+        assert_compression_constraints(LOG_N_ROWS, &rows, false);
+    }
+
+    #[test]
+    fn test_compression_constraints_chained() {
+        const LOG_N_ROWS: u32 = 4;
+        let rows = Rows::new(&[pad_message(&[0xab; 900]), pad_message(b"hello world")]);
+        assert_eq!(rows.len(), 1 << LOG_N_ROWS);
+        assert_compression_constraints(LOG_N_ROWS, &rows, true);
+    }
+
+    /// A cell only used in lookups changes the values added to the relations.
+    #[test]
+    fn test_compression_mutations() {
+        const LOG_N_ROWS: u32 = 4;
+        let rows = Rows::from_blocks(&gen_blocks(LOG_N_ROWS));
+
+        let (scheduling_trace, _) = gen_scheduling_trace_from_blocks(&rows.blocks);
+        let (trace, lookup_data) = gen_trace_chained(
+            &scheduling_trace,
+            &rows.msg_ids,
+            &rows.block_indices,
+            &rows.states,
+        );
+        let relations = Relations::dummy();
+        let (interaction_trace, claimed_sum) =
+            gen_interaction_trace(&lookup_data, &relations, false);
+        let n_columns = trace.len();
+
+        let eval = Eval {
+            log_size: LOG_N_ROWS,
+            relations: relations.clone(),
+            public_blocks: false,
+        };
+        let harness =
+            MutationHarness::new(eval, TreeVec::new(vec![vec![], trace, interaction_trace]));
+        let rounds_index = W_SIZE + ChainColumns::SIZE;
+        let sample = (0..2)
+            .chain(W_SIZE..rounds_index + RoundColumns::SIZE)
+            .chain(n_columns - FeedForwardColumns::SIZE * H.len()..n_columns);
+        let undetected = harness.undetected(mutation::columns(n_columns, sample), |polys| {
+            mutation::panics(|| {
+                assert_constraints_on_polys(
+                    polys,
+                    CanonicCoset::new(LOG_N_ROWS),
+                    |mut eval| {
+                        eval_compression_constraints(&mut eval, &relations, false);
+                    },
+                    claimed_sum,
+                )
+            })
+        });
+        mutation::assert_detected("compression", &undetected);
+    }
 }
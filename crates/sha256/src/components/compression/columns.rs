@@ -132,3 +132,27 @@ trace_columns!(
     new_a_low,
     new_a_high
 );
+
+trace_columns!(FeedForwardColumns, out_low, out_high, carry_low, carry_high);
+
+trace_columns!(
+    ChainColumns,
+    msg_id,
+    block_idx,
+    a_low,
+    a_high,
+    b_low,
+    b_high,
+    c_low,
+    c_high,
+    d_low,
+    d_high,
+    e_low,
+    e_high,
+    f_low,
+    f_high,
+    g_low,
+    g_high,
+    h_low,
+    h_high
+);
@@ -5,7 +5,8 @@
 
 use std::simd::u32x16;
 
-use itertools::izip;
+use num_traits::One;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use stwo::{
     core::{
         fields::{m31::BaseField, qm31::QM31},
@@ -15,68 +16,96 @@ use stwo::{
     prover::{
         backend::simd::{
             column::BaseColumn,
-            m31::{PackedM31, LOG_N_LANES},
+            m31::{PackedM31, LOG_N_LANES, N_LANES},
+            qm31::PackedQM31,
             SimdBackend,
         },
         poly::{circle::CircleEvaluation, BitReversedOrder},
     },
 };
 use stwo_constraint_framework::{LogupTraceGenerator, Relation};
-use utils::{combine, consume_col, consume_pair};
+use utils::{
+    combine, consume_col, consume_pair,
+    simd::{par_chunk_size, row_chunks_mut},
+    write_pair,
+};
 
 use crate::{
     components::{
-        compression::columns::{RoundColumns, RoundInteractionColumns},
+        compression::columns::{
+            ChainColumns, FeedForwardColumns, RoundColumns, RoundInteractionColumns,
+        },
         W_SIZE,
     },
     partitions::{pext_u32x16, BigSigma0, BigSigma1},
     relations::Relations,
     sha256::{
-        big_sigma_0_u32x16, big_sigma_1_u32x16, ch_left_u32x16, ch_right_u32x16, maj_u32x16, H, K,
-        N_COMPRESSION_ROUNDS,
+        big_sigma_0_u32x16, big_sigma_1_u32x16, ch_left_u32x16, ch_right_u32x16, maj_u32x16,
+        CHUNK_SIZE, H, K, N_COMPRESSION_ROUNDS,
     },
 };
 
-const N_COLUMNS: usize = W_SIZE + RoundColumns::SIZE * N_COMPRESSION_ROUNDS;
-const N_INTERACTION_COLUMNS: usize = W_SIZE + RoundInteractionColumns::SIZE * N_COMPRESSION_ROUNDS;
+const ROUNDS_INDEX: usize = W_SIZE + ChainColumns::SIZE;
+const FEED_FORWARD_INDEX: usize = ROUNDS_INDEX + RoundColumns::SIZE * N_COMPRESSION_ROUNDS;
+const N_COLUMNS: usize = FEED_FORWARD_INDEX + FeedForwardColumns::SIZE * H.len();
 
+// The chain columns come last in the lookup data, so that the preprocessed witnesses can keep
+// indexing the rounds right after W.
+const FEED_FORWARD_INTERACTION_INDEX: usize =
+    W_SIZE + RoundInteractionColumns::SIZE * N_COMPRESSION_ROUNDS;
+const CHAIN_INTERACTION_INDEX: usize =
+    FEED_FORWARD_INTERACTION_INDEX + FeedForwardColumns::SIZE * H.len();
+const N_INTERACTION_COLUMNS: usize = CHAIN_INTERACTION_INDEX + ChainColumns::SIZE;
+
+/// Compress each row from the initial hash value, as its own single-block message.
 #[allow(clippy::type_complexity)]
 pub fn gen_trace(
     w: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
 ) -> (
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     Vec<Vec<u32x16>>,
+) {
+    let n_rows = w[0].data.len() * N_LANES;
+    gen_trace_chained(
+        w,
+        &(0..n_rows as u32).collect::<Vec<_>>(),
+        &vec![0; n_rows],
+        &vec![H; n_rows],
+    )
+}
+
+/// Compress each row from the given state, tagged with its message and block index.
+#[allow(clippy::type_complexity)]
+pub fn gen_trace_chained(
+    w: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    msg_ids: &[u32],
+    block_indices: &[u32],
+    states: &[[u32; 8]],
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Vec<Vec<u32x16>>,
 ) {
     let simd_size = w[0].data.len();
+    assert_eq!(msg_ids.len(), simd_size * N_LANES);
+    assert_eq!(block_indices.len(), simd_size * N_LANES);
+    assert_eq!(states.len(), simd_size * N_LANES);
 
-    // Initialize vec for all groups of columns
+    // Preallocate all groups of columns, filled in place
     let mut evals: Vec<Vec<u32x16>> = (0..N_COLUMNS)
-        .map(|_| Vec::with_capacity(simd_size))
+        .map(|_| vec![u32x16::splat(0); simd_size])
         .collect::<Vec<_>>();
     let mut lookup_data: Vec<Vec<u32x16>> = (0..N_INTERACTION_COLUMNS)
-        .map(|_| Vec::with_capacity(simd_size))
+        .map(|_| vec![u32x16::splat(0); simd_size])
         .collect::<Vec<_>>();
 
-    // Generate round constants
-    let k: [u32x16; K.len() * 2] = K
-        .iter()
-        .flat_map(|k| [u32x16::splat(k & 0xffff), u32x16::splat(k >> 16)])
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
-
-    // Get initial hash value
-    let mut hash_buffer: [Vec<u32x16>; H.len() * 2] = H
-        .iter()
-        .flat_map(|h| {
-            [
-                vec![u32x16::splat(h & 0xffff); simd_size],
-                vec![u32x16::splat(h >> 16); simd_size],
-            ]
-        })
-        .collect::<Vec<_>>()
-        .try_into()
-        .unwrap();
+    // Get the state each row starts from
+    let state: [Vec<u32x16>; H.len() * 2] = std::array::from_fn(|i| {
+        let word = states.iter().map(|state| state[i / 2]);
+        match i % 2 {
+            0 => pack(word.map(|word| word & 0xffff)),
+            _ => pack(word.map(|word| word >> 16)),
+        }
+    });
 
     // Fill initial trace and lookup data
     evals
@@ -104,40 +133,98 @@ pub fn gen_trace(
                 .collect();
         });
 
+    // Fill chain columns
+    let chain = [
+        pack(msg_ids.iter().copied()),
+        pack(block_indices.iter().copied()),
+    ]
+    .into_iter()
+    .chain(state.iter().cloned());
+    for (i, values) in chain.enumerate() {
+        evals[W_SIZE + i] = values.clone();
+        lookup_data[CHAIN_INTERACTION_INDEX + i] = values;
+    }
+
+    // Each chunk of rows is filled by its own thread
+    let chunk_size = par_chunk_size(simd_size);
+    row_chunks_mut(&mut evals, chunk_size)
+        .into_par_iter()
+        .zip(row_chunks_mut(&mut lookup_data, chunk_size))
+        .enumerate()
+        .for_each(|(chunk, (mut evals, mut lookup_data))| {
+            let rows = chunk * chunk_size..chunk * chunk_size + evals[0].len();
+            let state: [&[u32x16]; H.len() * 2] = std::array::from_fn(|i| &state[i][rows.clone()]);
+            gen_rows(&mut evals, &mut lookup_data, &state);
+        });
+
+    let domain = CanonicCoset::new(simd_size.ilog2() + LOG_N_LANES).circle_domain();
+    let trace = evals
+        .into_iter()
+        .map(|values| {
+            CircleEvaluation::new(
+                domain,
+                BaseColumn::from_simd(
+                    values
+                        .into_iter()
+                        .map(|simd_chunk| unsafe { PackedM31::from_simd_unchecked(simd_chunk) })
+                        .collect(),
+                ),
+            )
+        })
+        .collect();
+
+    (trace, lookup_data)
+}
+
+/// Fill the rounds and feed-forward columns of a chunk of rows, compressed from `state`.
+fn gen_rows(
+    evals: &mut [&mut [u32x16]],
+    lookup_data: &mut [&mut [u32x16]],
+    state: &[&[u32x16]; H.len() * 2],
+) {
+    // Generate round constants
+    let k: [u32x16; K.len() * 2] = K
+        .iter()
+        .flat_map(|k| [u32x16::splat(k & 0xffff), u32x16::splat(k >> 16)])
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+
+    let mut hash_buffer = state.map(|column| column.to_vec());
+
     for round in 0..N_COMPRESSION_ROUNDS {
-        let index = W_SIZE + round * RoundColumns::SIZE;
+        let index = ROUNDS_INDEX + round * RoundColumns::SIZE;
         let interaction_index = W_SIZE + round * RoundInteractionColumns::SIZE;
 
-        let a_low = &hash_buffer[0].clone();
-        let a_high = &hash_buffer[1].clone();
-        let b_low = &hash_buffer[2].clone();
-        let b_high = &hash_buffer[3].clone();
-        let c_low = &hash_buffer[4].clone();
-        let c_high = &hash_buffer[5].clone();
-        let d_low = &hash_buffer[6].clone();
-        let d_high = &hash_buffer[7].clone();
-        let e_low = &hash_buffer[8].clone();
-        let e_high = &hash_buffer[9].clone();
-        let f_low = &hash_buffer[10].clone();
-        let f_high = &hash_buffer[11].clone();
-        let g_low = &hash_buffer[12].clone();
-        let g_high = &hash_buffer[13].clone();
-        let h_low = &hash_buffer[14].clone();
-        let h_high = &hash_buffer[15].clone();
-
         // Load K value
         let k_low = k[2 * round];
         let k_high = k[2 * round + 1];
 
-        for simd_row in 0..simd_size {
-            // Load W value
+        for simd_row in 0..state[0].len() {
+            // Load the working variables and W value
+            let a_low = hash_buffer[0][simd_row];
+            let a_high = hash_buffer[1][simd_row];
+            let b_low = hash_buffer[2][simd_row];
+            let b_high = hash_buffer[3][simd_row];
+            let c_low = hash_buffer[4][simd_row];
+            let c_high = hash_buffer[5][simd_row];
+            let d_low = hash_buffer[6][simd_row];
+            let d_high = hash_buffer[7][simd_row];
+            let e_low = hash_buffer[8][simd_row];
+            let e_high = hash_buffer[9][simd_row];
+            let f_low = hash_buffer[10][simd_row];
+            let f_high = hash_buffer[11][simd_row];
+            let g_low = hash_buffer[12][simd_row];
+            let g_high = hash_buffer[13][simd_row];
+            let h_low = hash_buffer[14][simd_row];
+            let h_high = hash_buffer[15][simd_row];
             let w_low = evals[2 * round][simd_row];
             let w_high = evals[2 * round + 1][simd_row];
 
             // BIG_SIGMA1
             // Decomposition over I0
-            let e_i0_low = e_low[simd_row] & u32x16::splat(BigSigma1::I0_L);
-            let e_i0_high = e_high[simd_row] & u32x16::splat(BigSigma1::I0_H);
+            let e_i0_low = e_low & u32x16::splat(BigSigma1::I0_L);
+            let e_i0_high = e_high & u32x16::splat(BigSigma1::I0_H);
             let sigma_1 = big_sigma_1_u32x16(e_i0_low + (e_i0_high << 16));
             let sigma_1_o0_low = sigma_1 & u32x16::splat(BigSigma1::O0_L);
             let sigma_1_o0_high = (sigma_1 >> 16) & u32x16::splat(BigSigma1::O0_H);
@@ -145,8 +232,8 @@ pub fn gen_trace(
             let sigma_1_o20_pext = pext_u32x16(sigma_1_o20, BigSigma1::O2);
 
             // Decomposition over I1
-            let e_i1_low = e_low[simd_row] & u32x16::splat(BigSigma1::I1_L);
-            let e_i1_high = e_high[simd_row] & u32x16::splat(BigSigma1::I1_H);
+            let e_i1_low = e_low & u32x16::splat(BigSigma1::I1_L);
+            let e_i1_high = e_high & u32x16::splat(BigSigma1::I1_H);
             let sigma_1 = big_sigma_1_u32x16(e_i1_low + (e_i1_high << 16));
             let sigma_1_o1_low = sigma_1 & u32x16::splat(BigSigma1::O1_L);
             let sigma_1_o1_high = (sigma_1 >> 16) & u32x16::splat(BigSigma1::O1_H);
@@ -164,20 +251,20 @@ pub fn gen_trace(
 
             // CH
             // left side
-            let f_i0_low = f_low[simd_row] & u32x16::splat(BigSigma1::I0_L);
-            let f_i0_high = f_high[simd_row] & u32x16::splat(BigSigma1::I0_H);
-            let f_i1_low = f_low[simd_row] & u32x16::splat(BigSigma1::I1_L);
-            let f_i1_high = f_high[simd_row] & u32x16::splat(BigSigma1::I1_H);
+            let f_i0_low = f_low & u32x16::splat(BigSigma1::I0_L);
+            let f_i0_high = f_high & u32x16::splat(BigSigma1::I0_H);
+            let f_i1_low = f_low & u32x16::splat(BigSigma1::I1_L);
+            let f_i1_high = f_high & u32x16::splat(BigSigma1::I1_H);
             let ch_left_i0_low = ch_left_u32x16(e_i0_low, f_i0_low);
             let ch_left_i0_high = ch_left_u32x16(e_i0_high, f_i0_high);
             let ch_left_i1_low = ch_left_u32x16(e_i1_low, f_i1_low);
             let ch_left_i1_high = ch_left_u32x16(e_i1_high, f_i1_high);
 
             // right side
-            let g_i0_low = g_low[simd_row] & u32x16::splat(BigSigma1::I0_L);
-            let g_i0_high = g_high[simd_row] & u32x16::splat(BigSigma1::I0_H);
-            let g_i1_low = g_low[simd_row] & u32x16::splat(BigSigma1::I1_L);
-            let g_i1_high = g_high[simd_row] & u32x16::splat(BigSigma1::I1_H);
+            let g_i0_low = g_low & u32x16::splat(BigSigma1::I0_L);
+            let g_i0_high = g_high & u32x16::splat(BigSigma1::I0_H);
+            let g_i1_low = g_low & u32x16::splat(BigSigma1::I1_L);
+            let g_i1_high = g_high & u32x16::splat(BigSigma1::I1_H);
             let ch_right_i0_low = ch_right_u32x16(e_i0_low, g_i0_low);
             let ch_right_i0_high = ch_right_u32x16(e_i0_high, g_i0_high);
             let ch_right_i1_low = ch_right_u32x16(e_i1_low, g_i1_low);
@@ -189,9 +276,9 @@ pub fn gen_trace(
 
             // BIG_SIGMA0
             // Decomposition over I0
-            let a_i0_low = a_low[simd_row] & u32x16::splat(BigSigma0::I0_L);
-            let a_i0_high_0 = a_high[simd_row] & u32x16::splat(BigSigma0::I0_H0);
-            let a_i0_high_1 = (a_high[simd_row] >> 8) & u32x16::splat(BigSigma0::I0_H1);
+            let a_i0_low = a_low & u32x16::splat(BigSigma0::I0_L);
+            let a_i0_high_0 = a_high & u32x16::splat(BigSigma0::I0_H0);
+            let a_i0_high_1 = (a_high >> 8) & u32x16::splat(BigSigma0::I0_H1);
 
             let sigma_0 = big_sigma_0_u32x16(a_i0_low + (a_i0_high_0 << 16) + (a_i0_high_1 << 24));
             let sigma_0_o0_low = sigma_0 & u32x16::splat(BigSigma0::O0_L);
@@ -200,9 +287,9 @@ pub fn gen_trace(
             let sigma_0_o20_pext = pext_u32x16(sigma_0_o20, BigSigma0::O2);
 
             // Decomposition over I1
-            let a_i1_low_0 = a_low[simd_row] & u32x16::splat(BigSigma0::I1_L0);
-            let a_i1_low_1 = (a_low[simd_row] >> 8) & u32x16::splat(BigSigma0::I1_L1);
-            let a_i1_high = a_high[simd_row] & u32x16::splat(BigSigma0::I1_H);
+            let a_i1_low_0 = a_low & u32x16::splat(BigSigma0::I1_L0);
+            let a_i1_low_1 = (a_low >> 8) & u32x16::splat(BigSigma0::I1_L1);
+            let a_i1_high = a_high & u32x16::splat(BigSigma0::I1_H);
 
             let sigma_0 = big_sigma_0_u32x16(a_i1_low_0 + (a_i1_low_1 << 8) + (a_i1_high << 16));
             let sigma_0_o1_low = sigma_0 & u32x16::splat(BigSigma0::O1_L);
@@ -220,18 +307,18 @@ pub fn gen_trace(
             let sigma_0_high = sigma_0_o0_high + sigma_0_o1_high + sigma_0_o2_high;
 
             // MAJ
-            let b_i0_low = b_low[simd_row] & u32x16::splat(BigSigma0::I0_L);
-            let b_i0_high_0 = b_high[simd_row] & u32x16::splat(BigSigma0::I0_H0);
-            let b_i0_high_1 = (b_high[simd_row] >> 8) & u32x16::splat(BigSigma0::I0_H1);
-            let b_i1_low_0 = b_low[simd_row] & u32x16::splat(BigSigma0::I1_L0);
-            let b_i1_low_1 = (b_low[simd_row] >> 8) & u32x16::splat(BigSigma0::I1_L1);
-            let b_i1_high = b_high[simd_row] & u32x16::splat(BigSigma0::I1_H);
-            let c_i0_low = c_low[simd_row] & u32x16::splat(BigSigma0::I0_L);
-            let c_i0_high_0 = c_high[simd_row] & u32x16::splat(BigSigma0::I0_H0);
-            let c_i0_high_1 = (c_high[simd_row] >> 8) & u32x16::splat(BigSigma0::I0_H1);
-            let c_i1_low_0 = c_low[simd_row] & u32x16::splat(BigSigma0::I1_L0);
-            let c_i1_low_1 = (c_low[simd_row] >> 8) & u32x16::splat(BigSigma0::I1_L1);
-            let c_i1_high = c_high[simd_row] & u32x16::splat(BigSigma0::I1_H);
+            let b_i0_low = b_low & u32x16::splat(BigSigma0::I0_L);
+            let b_i0_high_0 = b_high & u32x16::splat(BigSigma0::I0_H0);
+            let b_i0_high_1 = (b_high >> 8) & u32x16::splat(BigSigma0::I0_H1);
+            let b_i1_low_0 = b_low & u32x16::splat(BigSigma0::I1_L0);
+            let b_i1_low_1 = (b_low >> 8) & u32x16::splat(BigSigma0::I1_L1);
+            let b_i1_high = b_high & u32x16::splat(BigSigma0::I1_H);
+            let c_i0_low = c_low & u32x16::splat(BigSigma0::I0_L);
+            let c_i0_high_0 = c_high & u32x16::splat(BigSigma0::I0_H0);
+            let c_i0_high_1 = (c_high >> 8) & u32x16::splat(BigSigma0::I0_H1);
+            let c_i1_low_0 = c_low & u32x16::splat(BigSigma0::I1_L0);
+            let c_i1_low_1 = (c_low >> 8) & u32x16::splat(BigSigma0::I1_L1);
+            let c_i1_high = c_high & u32x16::splat(BigSigma0::I1_H);
             let maj_i0_low = maj_u32x16(a_i0_low, b_i0_low, c_i0_low);
             let maj_i0_high_0 = maj_u32x16(a_i0_high_0, b_i0_high_0, c_i0_high_0);
             let maj_i0_high_1 = maj_u32x16(a_i0_high_1, b_i0_high_1, c_i0_high_1);
@@ -244,15 +331,15 @@ pub fn gen_trace(
             let maj_high = maj_i0_high_0 + (maj_i0_high_1 << 8) + maj_i1_high;
 
             // TEMP
-            let temp1_low = h_low[simd_row] + sigma1_low + ch_low + k_low + w_low;
-            let temp1_high = h_high[simd_row] + sigma1_high + ch_high + k_high + w_high;
+            let temp1_low = h_low + sigma1_low + ch_low + k_low + w_low;
+            let temp1_high = h_high + sigma1_high + ch_high + k_high + w_high;
             let temp2_low = sigma_0_low + maj_low;
             let temp2_high = sigma_0_high + maj_high;
 
-            let e_carry_low = (temp1_low + d_low[simd_row]) >> 16;
-            let e_carry_high = (temp1_high + d_high[simd_row] + e_carry_low) >> 16;
-            let new_e_low = temp1_low + d_low[simd_row] - (e_carry_low << 16);
-            let new_e_high = temp1_high + d_high[simd_row] + e_carry_low - (e_carry_high << 16);
+            let e_carry_low = (temp1_low + d_low) >> 16;
+            let e_carry_high = (temp1_high + d_high + e_carry_low) >> 16;
+            let new_e_low = temp1_low + d_low - (e_carry_low << 16);
+            let new_e_high = temp1_high + d_high + e_carry_low - (e_carry_high << 16);
             let a_carry_low = (temp1_low + temp2_low) >> 16;
             let a_carry_high = (temp1_high + temp2_high + a_carry_low) >> 16;
             let new_a_low = temp1_low + temp2_low - (a_carry_low << 16);
@@ -313,7 +400,7 @@ pub fn gen_trace(
                 a_carry_high: &a_carry_high,
             };
             for (i, value) in trace_values.iter().enumerate() {
-                evals[index + i].push(*value);
+                evals[index + i][simd_row] = *value;
             }
 
             let interaction_values: RoundInteractionColumns<u32x16> = RoundInteractionColumns {
@@ -387,185 +474,61 @@ pub fn gen_trace(
                 new_a_high: &new_a_high,
             };
             for (i, value) in interaction_values.iter().enumerate() {
-                lookup_data[interaction_index + i].push(*value);
+                lookup_data[interaction_index + i][simd_row] = *value;
             }
+
+            // h and d are not read anymore, they take the new a and e values
+            hash_buffer[14][simd_row] = new_a_low;
+            hash_buffer[15][simd_row] = new_a_high;
+            hash_buffer[6][simd_row] = new_e_low;
+            hash_buffer[7][simd_row] = new_e_high;
         }
 
-        update_hash_buffer(&mut hash_buffer, &evals, round);
+        // Shift the working variables, moving the new a and e into place
+        hash_buffer.rotate_right(2);
     }
 
-    let domain = CanonicCoset::new(simd_size.ilog2() + LOG_N_LANES).circle_domain();
-    let trace = evals
-        .into_iter()
-        .map(|values| {
-            CircleEvaluation::new(
-                domain,
-                BaseColumn::from_simd(
-                    values
-                        .into_iter()
-                        .map(|simd_chunk| unsafe { PackedM31::from_simd_unchecked(simd_chunk) })
-                        .collect(),
-                ),
-            )
-        })
-        .collect();
-
-    (trace, lookup_data)
-}
-
-/// Update the hash buffer with the values from the trace
-fn update_hash_buffer(hash_buffer: &mut [Vec<u32x16>], evals: &[Vec<u32x16>], round: usize) {
-    let d_low = &hash_buffer[6];
-    let d_high = &hash_buffer[7];
-    let h_low = &hash_buffer[14];
-    let h_high = &hash_buffer[15];
-
-    let w_low = evals[2 * round].clone();
-    let w_high = evals[2 * round + 1].clone();
-
-    let k_low = u32x16::splat(K[round] & 0xffff);
-    let k_high = u32x16::splat(K[round] >> 16);
-
-    let index = W_SIZE + RoundColumns::SIZE * round;
-    let RoundColumns {
-        e_i0_low: _,
-        e_i0_high: _,
-        sigma_1_o0_low,
-        sigma_1_o0_high,
-        sigma_1_o20_pext: _,
-        sigma_1_o1_low,
-        sigma_1_o1_high,
-        sigma_1_o21_pext: _,
-        sigma_1_o2_low,
-        sigma_1_o2_high,
-        f_i0_low: _,
-        f_i0_high: _,
-        ch_left_i0_low,
-        ch_left_i0_high,
-        ch_left_i1_low,
-        ch_left_i1_high,
-        g_i0_low: _,
-        g_i0_high: _,
-        ch_right_i0_low,
-        ch_right_i0_high,
-        ch_right_i1_low,
-        ch_right_i1_high,
-        a_i0_high_0: _,
-        a_i0_high_1: _,
-        a_i1_low_0: _,
-        a_i1_low_1: _,
-        sigma_0_o0_low,
-        sigma_0_o0_high,
-        sigma_0_o20_pext: _,
-        sigma_0_o1_low,
-        sigma_0_o1_high,
-        sigma_0_o21_pext: _,
-        sigma_0_o2_low,
-        sigma_0_o2_high,
-        b_i0_high_0: _,
-        b_i0_high_1: _,
-        b_i1_low_0: _,
-        b_i1_low_1: _,
-        c_i0_high_0: _,
-        c_i0_high_1: _,
-        c_i1_low_0: _,
-        c_i1_low_1: _,
-        maj_i0_low,
-        maj_i0_high_0,
-        maj_i0_high_1,
-        maj_i1_low_0,
-        maj_i1_low_1,
-        maj_i1_high,
-        e_carry_low,
-        e_carry_high,
-        a_carry_low,
-        a_carry_high,
-    } = RoundColumns::from_slice(&evals[index..(index + RoundColumns::SIZE)]);
-
-    let sigma1_low: Vec<u32x16> = izip!(sigma_1_o0_low, sigma_1_o1_low, sigma_1_o2_low)
-        .map(|(a, b, c)| a + b + c)
-        .collect();
-    let sigma1_high: Vec<u32x16> = izip!(sigma_1_o0_high, sigma_1_o1_high, sigma_1_o2_high)
-        .map(|(a, b, c)| a + b + c)
-        .collect();
-
-    let ch_low: Vec<u32x16> = izip!(
-        ch_left_i0_low,
-        ch_left_i1_low,
-        ch_right_i0_low,
-        ch_right_i1_low
-    )
-    .map(|(a, b, c, d)| a + b + c + d)
-    .collect();
-    let ch_high: Vec<u32x16> = izip!(
-        ch_left_i0_high,
-        ch_left_i1_high,
-        ch_right_i0_high,
-        ch_right_i1_high
-    )
-    .map(|(a, b, c, d)| a + b + c + d)
-    .collect();
-
-    let sigma_0_high: Vec<u32x16> = izip!(sigma_0_o0_high, sigma_0_o1_high, sigma_0_o2_high)
-        .map(|(a, b, c)| a + b + c)
-        .collect();
-    let sigma_0_low: Vec<u32x16> = izip!(sigma_0_o0_low, sigma_0_o1_low, sigma_0_o2_low)
-        .map(|(a, b, c)| a + b + c)
-        .collect();
-
-    let maj_high: Vec<u32x16> = izip!(maj_i0_high_0, maj_i0_high_1, maj_i1_high)
-        .map(|(a, b, c)| a + (b << 8) + c)
-        .collect();
-    let maj_low: Vec<u32x16> = izip!(maj_i0_low, maj_i1_low_0, maj_i1_low_1)
-        .map(|(a, b, c)| a + b + (c << 8))
-        .collect();
-
-    let temp1_high: Vec<u32x16> = izip!(h_high, sigma1_high, ch_high, w_high)
-        .map(|(a, b, c, d)| a + b + c + d + k_high)
-        .collect();
-    let temp1_low: Vec<u32x16> = izip!(h_low, sigma1_low, ch_low, w_low)
-        .map(|(a, b, c, d)| a + b + c + d + k_low)
-        .collect();
-
-    let temp2_high: Vec<u32x16> = izip!(sigma_0_high, maj_high).map(|(a, b)| a + b).collect();
-    let temp2_low: Vec<u32x16> = izip!(sigma_0_low, maj_low).map(|(a, b)| a + b).collect();
+    // Feed-forward: out = state + hash_buffer
+    for i in 0..H.len() {
+        let index = FEED_FORWARD_INDEX + i * FeedForwardColumns::SIZE;
+        let interaction_index = FEED_FORWARD_INTERACTION_INDEX + i * FeedForwardColumns::SIZE;
 
-    let e_low: Vec<u32x16> = izip!(d_low.clone(), temp1_low.clone(), e_carry_low)
-        .map(|(a, b, carry_low)| a + b - (carry_low << u32x16::splat(16)))
-        .collect();
-    let e_high: Vec<u32x16> = izip!(d_high, temp1_high.clone(), e_carry_low, e_carry_high)
-        .map(|(a, b, carry_low, carry_high)| a + b + carry_low - (carry_high << u32x16::splat(16)))
-        .collect();
+        for simd_row in 0..state[0].len() {
+            let sum_low = hash_buffer[2 * i][simd_row] + state[2 * i][simd_row];
+            let carry_low = sum_low >> 16;
+            let out_low = sum_low & u32x16::splat(0xffff);
+            let sum_high =
+                hash_buffer[2 * i + 1][simd_row] + state[2 * i + 1][simd_row] + carry_low;
+            let carry_high = sum_high >> 16;
+            let out_high = sum_high & u32x16::splat(0xffff);
 
-    let a_low: Vec<u32x16> = izip!(temp1_low, temp2_low, a_carry_low.clone())
-        .map(|(a, b, carry_low)| a + b - (carry_low << u32x16::splat(16)))
-        .collect();
-    let a_high = izip!(temp1_high, temp2_high, a_carry_low, a_carry_high)
-        .map(|(a, b, carry_low, carry_high)| a + b + carry_low - (carry_high << u32x16::splat(16)))
-        .collect();
+            let values: FeedForwardColumns<u32x16> = FeedForwardColumns {
+                out_low: &out_low,
+                out_high: &out_high,
+                carry_low: &carry_low,
+                carry_high: &carry_high,
+            };
+            for (j, value) in values.iter().enumerate() {
+                evals[index + j][simd_row] = *value;
+                lookup_data[interaction_index + j][simd_row] = *value;
+            }
+        }
+    }
+}
 
-    hash_buffer[15] = hash_buffer[13].clone(); // h_high = g_high
-    hash_buffer[14] = hash_buffer[12].clone(); // h_low = g_low
-    hash_buffer[13] = hash_buffer[11].clone(); // g_high = f_high
-    hash_buffer[12] = hash_buffer[10].clone(); // g_low = f_low
-    hash_buffer[11] = hash_buffer[9].clone(); // f_high = e_high
-    hash_buffer[10] = hash_buffer[8].clone(); // f_low = e_low
-    hash_buffer[9] = e_high; // e_high = d_high + temp1_high
-    hash_buffer[8] = e_low; // e_low = d_low + temp1_low
-    hash_buffer[7] = hash_buffer[5].clone(); // d_high = c_high
-    hash_buffer[6] = hash_buffer[4].clone(); // d_low = c_low
-    hash_buffer[5] = hash_buffer[3].clone(); // c_high = b_high
-    hash_buffer[4] = hash_buffer[2].clone(); // c_low = b_low
-    hash_buffer[3] = hash_buffer[1].clone(); // b_high = a_high
-    hash_buffer[2] = hash_buffer[0].clone(); // b_low = a_low
-    hash_buffer[1] = a_high; // a_high = temp1_high + temp2_high
-    hash_buffer[0] = a_low; // a_low = temp1_low + temp2_low
+/// Pack row values into SIMD vectors
+fn pack(values: impl Iterator<Item = u32>) -> Vec<u32x16> {
+    values
+        .array_chunks::<N_LANES>()
+        .map(u32x16::from_array)
+        .collect()
 }
 
 #[allow(clippy::cognitive_complexity)]
 pub fn gen_interaction_trace(
     lookup_data: &[Vec<u32x16>],
     relations: &Relations,
+    public_blocks: bool,
 ) -> (
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     QM31,
@@ -573,7 +536,9 @@ pub fn gen_interaction_trace(
     let simd_size = lookup_data[0].len();
     let mut interaction_trace = LogupTraceGenerator::new(simd_size.ilog2() + LOG_N_LANES);
 
-    for round in lookup_data[W_SIZE..].array_chunks::<{ RoundInteractionColumns::SIZE }>() {
+    for round in lookup_data[W_SIZE..FEED_FORWARD_INTERACTION_INDEX]
+        .array_chunks::<{ RoundInteractionColumns::SIZE }>()
+    {
         let RoundInteractionColumns {
             e_i0_low,
             e_i0_high,
@@ -801,21 +766,237 @@ pub fn gen_interaction_trace(
         );
     }
 
-    // Consume W emitted by scheduling
+    // Feed-forward
+    let mut out: Vec<&Vec<u32x16>> = Vec::with_capacity(H.len() * 2);
+    for word in lookup_data[FEED_FORWARD_INTERACTION_INDEX..CHAIN_INTERACTION_INDEX]
+        .array_chunks::<{ FeedForwardColumns::SIZE }>()
+    {
+        let FeedForwardColumns {
+            out_low,
+            out_high,
+            carry_low,
+            carry_high,
+        } = FeedForwardColumns::from_slice(word);
+        out.push(out_low);
+        out.push(out_high);
+
+        let carry_low = combine!(relations.range_check_add.add_4, [out_low, carry_low]);
+        let carry_high = combine!(relations.range_check_add.add_4, [out_high, carry_high]);
+
+        consume_pair!(interaction_trace; carry_low, carry_high);
+    }
+
+    // Consume the input state and emit the output state for the next block
+    let chain = &lookup_data[CHAIN_INTERACTION_INDEX..];
+    let (msg_id, block_idx) = (&chain[0], &chain[1]);
+    let next_block_idx: Vec<u32x16> = block_idx.iter().map(|x| *x + u32x16::splat(1)).collect();
+    let chain_in = combine!(relations.chain, chain.iter().collect::<Vec<_>>());
+    let chain_out = combine!(
+        relations.chain,
+        [msg_id, &next_block_idx]
+            .into_iter()
+            .chain(out)
+            .collect::<Vec<_>>()
+    );
+    write_pair!(
+        std::iter::repeat(-PackedQM31::one()),
+        chain_in,
+        std::iter::repeat(PackedQM31::one()),
+        chain_out,
+        interaction_trace
+    );
+
+    // Emit the message block consumed by the verifier, and consume W emitted by scheduling
     let w = combine!(relations.w, &lookup_data[..W_SIZE]);
-    consume_col!(w, interaction_trace);
+    if public_blocks {
+        let message = combine!(
+            relations.message,
+            [msg_id, block_idx]
+                .into_iter()
+                .chain(&lookup_data[..CHUNK_SIZE])
+                .collect::<Vec<_>>()
+        );
+        write_pair!(
+            std::iter::repeat(PackedQM31::one()),
+            message,
+            std::iter::repeat(-PackedQM31::one()),
+            w,
+            interaction_trace
+        );
+    } else {
+        consume_col!(w, interaction_trace);
+    }
 
     interaction_trace.finalize_last()
 }
 
 #[cfg(test)]
 mod tests {
+    use itertools::izip;
+    use proptest::{collection::vec, prelude::*};
+    use sha2::digest::generic_array::GenericArray;
+
     use super::*;
     use crate::{
-        components::scheduling::witness::gen_trace as gen_schedule,
-        sha256::{process_chunk_u32x16, CHUNK_SIZE},
+        components::scheduling::witness::{
+            gen_trace as gen_schedule, gen_trace_from_blocks as gen_schedule_from_blocks,
+        },
+        sha256::process_chunk_u32x16,
     };
 
+    /// Update the hash buffer from the trace values, independently of the trace generation
+    fn update_hash_buffer(hash_buffer: &mut [Vec<u32x16>], evals: &[Vec<u32x16>], round: usize) {
+        let d_low = &hash_buffer[6];
+        let d_high = &hash_buffer[7];
+        let h_low = &hash_buffer[14];
+        let h_high = &hash_buffer[15];
+
+        let w_low = evals[2 * round].clone();
+        let w_high = evals[2 * round + 1].clone();
+
+        let k_low = u32x16::splat(K[round] & 0xffff);
+        let k_high = u32x16::splat(K[round] >> 16);
+
+        let index = ROUNDS_INDEX + RoundColumns::SIZE * round;
+        let RoundColumns {
+            e_i0_low: _,
+            e_i0_high: _,
+            sigma_1_o0_low,
+            sigma_1_o0_high,
+            sigma_1_o20_pext: _,
+            sigma_1_o1_low,
+            sigma_1_o1_high,
+            sigma_1_o21_pext: _,
+            sigma_1_o2_low,
+            sigma_1_o2_high,
+            f_i0_low: _,
+            f_i0_high: _,
+            ch_left_i0_low,
+            ch_left_i0_high,
+            ch_left_i1_low,
+            ch_left_i1_high,
+            g_i0_low: _,
+            g_i0_high: _,
+            ch_right_i0_low,
+            ch_right_i0_high,
+            ch_right_i1_low,
+            ch_right_i1_high,
+            a_i0_high_0: _,
+            a_i0_high_1: _,
+            a_i1_low_0: _,
+            a_i1_low_1: _,
+            sigma_0_o0_low,
+            sigma_0_o0_high,
+            sigma_0_o20_pext: _,
+            sigma_0_o1_low,
+            sigma_0_o1_high,
+            sigma_0_o21_pext: _,
+            sigma_0_o2_low,
+            sigma_0_o2_high,
+            b_i0_high_0: _,
+            b_i0_high_1: _,
+            b_i1_low_0: _,
+            b_i1_low_1: _,
+            c_i0_high_0: _,
+            c_i0_high_1: _,
+            c_i1_low_0: _,
+            c_i1_low_1: _,
+            maj_i0_low,
+            maj_i0_high_0,
+            maj_i0_high_1,
+            maj_i1_low_0,
+            maj_i1_low_1,
+            maj_i1_high,
+            e_carry_low,
+            e_carry_high,
+            a_carry_low,
+            a_carry_high,
+        } = RoundColumns::from_slice(&evals[index..(index + RoundColumns::SIZE)]);
+
+        let sigma1_low: Vec<u32x16> = izip!(sigma_1_o0_low, sigma_1_o1_low, sigma_1_o2_low)
+            .map(|(a, b, c)| a + b + c)
+            .collect();
+        let sigma1_high: Vec<u32x16> = izip!(sigma_1_o0_high, sigma_1_o1_high, sigma_1_o2_high)
+            .map(|(a, b, c)| a + b + c)
+            .collect();
+
+        let ch_low: Vec<u32x16> = izip!(
+            ch_left_i0_low,
+            ch_left_i1_low,
+            ch_right_i0_low,
+            ch_right_i1_low
+        )
+        .map(|(a, b, c, d)| a + b + c + d)
+        .collect();
+        let ch_high: Vec<u32x16> = izip!(
+            ch_left_i0_high,
+            ch_left_i1_high,
+            ch_right_i0_high,
+            ch_right_i1_high
+        )
+        .map(|(a, b, c, d)| a + b + c + d)
+        .collect();
+
+        let sigma_0_high: Vec<u32x16> = izip!(sigma_0_o0_high, sigma_0_o1_high, sigma_0_o2_high)
+            .map(|(a, b, c)| a + b + c)
+            .collect();
+        let sigma_0_low: Vec<u32x16> = izip!(sigma_0_o0_low, sigma_0_o1_low, sigma_0_o2_low)
+            .map(|(a, b, c)| a + b + c)
+            .collect();
+
+        let maj_high: Vec<u32x16> = izip!(maj_i0_high_0, maj_i0_high_1, maj_i1_high)
+            .map(|(a, b, c)| a + (b << 8) + c)
+            .collect();
+        let maj_low: Vec<u32x16> = izip!(maj_i0_low, maj_i1_low_0, maj_i1_low_1)
+            .map(|(a, b, c)| a + b + (c << 8))
+            .collect();
+
+        let temp1_high: Vec<u32x16> = izip!(h_high, sigma1_high, ch_high, w_high)
+            .map(|(a, b, c, d)| a + b + c + d + k_high)
+            .collect();
+        let temp1_low: Vec<u32x16> = izip!(h_low, sigma1_low, ch_low, w_low)
+            .map(|(a, b, c, d)| a + b + c + d + k_low)
+            .collect();
+
+        let temp2_high: Vec<u32x16> = izip!(sigma_0_high, maj_high).map(|(a, b)| a + b).collect();
+        let temp2_low: Vec<u32x16> = izip!(sigma_0_low, maj_low).map(|(a, b)| a + b).collect();
+
+        let e_low: Vec<u32x16> = izip!(d_low.clone(), temp1_low.clone(), e_carry_low)
+            .map(|(a, b, carry_low)| a + b - (carry_low << u32x16::splat(16)))
+            .collect();
+        let e_high: Vec<u32x16> = izip!(d_high, temp1_high.clone(), e_carry_low, e_carry_high)
+            .map(|(a, b, carry_low, carry_high)| {
+                a + b + carry_low - (carry_high << u32x16::splat(16))
+            })
+            .collect();
+
+        let a_low: Vec<u32x16> = izip!(temp1_low, temp2_low, a_carry_low.clone())
+            .map(|(a, b, carry_low)| a + b - (carry_low << u32x16::splat(16)))
+            .collect();
+        let a_high = izip!(temp1_high, temp2_high, a_carry_low, a_carry_high)
+            .map(|(a, b, carry_low, carry_high)| {
+                a + b + carry_low - (carry_high << u32x16::splat(16))
+            })
+            .collect();
+
+        hash_buffer[15] = hash_buffer[13].clone(); // h_high = g_high
+        hash_buffer[14] = hash_buffer[12].clone(); // h_low = g_low
+        hash_buffer[13] = hash_buffer[11].clone(); // g_high = f_high
+        hash_buffer[12] = hash_buffer[10].clone(); // g_low = f_low
+        hash_buffer[11] = hash_buffer[9].clone(); // f_high = e_high
+        hash_buffer[10] = hash_buffer[8].clone(); // f_low = e_low
+        hash_buffer[9] = e_high; // e_high = d_high + temp1_high
+        hash_buffer[8] = e_low; // e_low = d_low + temp1_low
+        hash_buffer[7] = hash_buffer[5].clone(); // d_high = c_high
+        hash_buffer[6] = hash_buffer[4].clone(); // d_low = c_low
+        hash_buffer[5] = hash_buffer[3].clone(); // c_high = b_high
+        hash_buffer[4] = hash_buffer[2].clone(); // c_low = b_low
+        hash_buffer[3] = hash_buffer[1].clone(); // b_high = a_high
+        hash_buffer[2] = hash_buffer[0].clone(); // b_low = a_low
+        hash_buffer[1] = a_high; // a_high = temp1_high + temp2_high
+        hash_buffer[0] = a_low; // a_low = temp1_low + temp2_low
+    }
+
     #[test]
     fn test_gen_trace_columns_count() {
         let (schedule, _) = gen_schedule(LOG_N_LANES);
@@ -823,6 +1004,19 @@ mod tests {
         assert_eq!(trace.len(), N_COLUMNS);
     }
 
+    #[test]
+    fn test_gen_trace_chunks() {
+        let (schedule, _) = gen_schedule(LOG_N_LANES + 3);
+        let gen_lookup_data = |n_threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(n_threads)
+                .build()
+                .unwrap()
+                .install(|| gen_trace(&schedule).1)
+        };
+        assert_eq!(gen_lookup_data(1), gen_lookup_data(3));
+    }
+
     #[test]
     fn test_gen_trace_values() {
         let log_size = LOG_N_LANES;
@@ -871,5 +1065,90 @@ mod tests {
         });
 
         assert_eq!(result, expected);
+
+        // Feed-forward columns hold the digest limbs
+        let digest: [u32x16; 8] = std::array::from_fn(|i| {
+            let FeedForwardColumns {
+                out_low, out_high, ..
+            } = FeedForwardColumns::from_slice(
+                &evals[FEED_FORWARD_INDEX + i * FeedForwardColumns::SIZE
+                    ..FEED_FORWARD_INDEX + (i + 1) * FeedForwardColumns::SIZE],
+            );
+            out_low[0] + (out_high[0] << 16)
+        });
+
+        assert_eq!(digest, expected);
+    }
+
+    /// Message batches of 16 to 64 rows, each row compressed from H or from a random state.
+    fn batches() -> impl Strategy<Value = Vec<([u32; 16], [u32; 8])>> {
+        (LOG_N_LANES..LOG_N_LANES + 3).prop_flat_map(|log_size| {
+            vec(
+                (any::<[u32; 16]>(), prop_oneof![Just(H), any::<[u32; 8]>()]),
+                1 << log_size,
+            )
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_gen_trace_against_sha2(batch in batches()) {
+            let (blocks, states): (Vec<[u32; 16]>, Vec<[u32; 8]>) = batch.into_iter().unzip();
+            let n_rows = blocks.len();
+            let (schedule, _) = gen_schedule_from_blocks(&blocks);
+            let (trace, _) = gen_trace_chained(
+                &schedule,
+                &(0..n_rows as u32).collect::<Vec<_>>(),
+                &vec![0; n_rows],
+                &states,
+            );
+            let evals: Vec<Vec<u32x16>> = trace
+                .iter()
+                .map(|eval| eval.data.iter().map(|x| x.into_simd()).collect())
+                .collect();
+
+            // Replay the rounds from the state of each row
+            let mut hash_buffer: Vec<Vec<u32x16>> = (0..H.len() * 2)
+                .map(|i| {
+                    let word = states.iter().map(|state| state[i / 2]);
+                    match i % 2 {
+                        0 => pack(word.map(|word| word & 0xffff)),
+                        _ => pack(word.map(|word| word >> 16)),
+                    }
+                })
+                .collect();
+            for round in 0..N_COMPRESSION_ROUNDS {
+                update_hash_buffer(&mut hash_buffer, &evals, round);
+            }
+
+            for (row, (block, state)) in blocks.iter().zip(&states).enumerate() {
+                let (simd_row, lane) = (row / N_LANES, row % N_LANES);
+                let limbs = |low: &Vec<u32x16>, high: &Vec<u32x16>| {
+                    low[simd_row][lane].wrapping_add(high[simd_row][lane] << 16)
+                };
+
+                let mut expected = *state;
+                let bytes: Vec<u8> = block.iter().flat_map(|word| word.to_be_bytes()).collect();
+                sha2::compress256(&mut expected, &[GenericArray::clone_from_slice(&bytes)]);
+
+                let replayed: [u32; 8] = std::array::from_fn(|i| {
+                    limbs(&hash_buffer[2 * i], &hash_buffer[2 * i + 1]).wrapping_add(state[i])
+                });
+                prop_assert_eq!(replayed, expected, "rounds of row {}", row);
+
+                let digest: [u32; 8] = std::array::from_fn(|i| {
+                    let index = FEED_FORWARD_INDEX + i * FeedForwardColumns::SIZE;
+                    let FeedForwardColumns {
+                        out_low, out_high, ..
+                    } = FeedForwardColumns::from_slice(
+                        &evals[index..index + FeedForwardColumns::SIZE],
+                    );
+                    limbs(out_low, out_high)
+                });
+                prop_assert_eq!(digest, expected, "digest of row {}", row);
+            }
+        }
     }
 }
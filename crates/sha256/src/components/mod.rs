@@ -1,9 +1,10 @@
-use std::simd::u32x16;
+use std::{collections::HashMap, simd::u32x16};
 
+use serde::{Deserialize, Serialize};
 use stwo::{
     core::{
         air::Component,
-        channel::MerkleChannel,
+        channel::{Channel, MerkleChannel},
         fields::{m31::BaseField, qm31::SecureField},
         pcs::TreeVec,
         ColumnVec,
@@ -18,15 +19,22 @@ use stwo::{
     },
 };
 use stwo_constraint_framework::{
-    relation_tracker::{add_to_relation_entries, RelationSummary, RelationTrackerEntry},
-    TraceLocationAllocator,
+    relation_tracker::add_to_relation_entries, FrameworkEval, TraceLocationAllocator,
 };
 use tracing::{span, Level};
 
-use crate::relations::Relations;
+use crate::{
+    diagnostics::{ComponentEntries, Diagnostics},
+    messages::Rows,
+    public::PublicData,
+    relations::Relations,
+    stats::ComponentStats,
+};
 pub const W_SIZE: usize = 128; // 128 u16 = 64 u32
 
 pub mod compression;
+#[cfg(test)]
+mod mutation;
 pub mod preprocessed;
 pub mod scheduling;
 
@@ -36,27 +44,48 @@ pub struct LookupData {
     pub preprocessed: preprocessed::Traces,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ClaimedSum {
     pub scheduling: SecureField,
     pub compression: SecureField,
     pub preprocessed: preprocessed::ClaimedSum,
 }
 
+impl ClaimedSum {
+    pub fn sum(&self) -> SecureField {
+        self.scheduling + self.compression + self.preprocessed.sum()
+    }
+
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        let mut sums = vec![self.scheduling, self.compression];
+        sums.extend(self.preprocessed.to_vec());
+        channel.mix_felts(&sums);
+    }
+}
+
+/// Generate the main trace for one message block per row.
 pub fn gen_trace(
-    log_size: u32,
+    rows: &Rows,
 ) -> (
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     LookupData,
 ) {
+    assert!(rows.len().is_power_of_two());
+    let log_size = rows.len().ilog2();
     assert!(log_size >= LOG_N_LANES);
 
     let span = span!(Level::INFO, "Scheduling").entered();
-    let (scheduling_trace, scheduling_lookup_data) = scheduling::witness::gen_trace(log_size);
+    let (scheduling_trace, scheduling_lookup_data) =
+        scheduling::witness::gen_trace_from_blocks(&rows.blocks);
     span.exit();
 
     let span = span!(Level::INFO, "Compression").entered();
-    let (compression_trace, compression_lookup_data) =
-        compression::witness::gen_trace(&scheduling_trace);
+    let (compression_trace, compression_lookup_data) = compression::witness::gen_trace_chained(
+        &scheduling_trace,
+        &rows.msg_ids,
+        &rows.block_indices,
+        &rows.states,
+    );
     span.exit();
 
     let span = span!(Level::INFO, "Preprocessed").entered();
@@ -84,6 +113,7 @@ pub fn gen_trace(
 pub fn gen_interaction_trace(
     lookup_data: LookupData,
     relations: &Relations,
+    public_blocks: bool,
 ) -> (
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     ClaimedSum,
@@ -95,7 +125,11 @@ pub fn gen_interaction_trace(
 
     let span = span!(Level::INFO, "Compression").entered();
     let (compression_interaction_trace, compression_claimed_sum) =
-        compression::witness::gen_interaction_trace(&lookup_data.compression, relations);
+        compression::witness::gen_interaction_trace(
+            &lookup_data.compression,
+            relations,
+            public_blocks,
+        );
     span.exit();
 
     let span = span!(Level::INFO, "Preprocessed").entered();
@@ -134,6 +168,7 @@ impl Components {
         location_allocator: &mut TraceLocationAllocator,
         relations: &Relations,
         claimed_sum: &ClaimedSum,
+        public_blocks: bool,
     ) -> Self {
         Self {
             scheduling: scheduling::air::Component::new(
@@ -149,6 +184,7 @@ impl Components {
                 compression::air::Eval {
                     log_size,
                     relations: relations.clone(),
+                    public_blocks,
                 },
                 claimed_sum.compression,
             ),
@@ -170,10 +206,19 @@ impl Components {
         provers
     }
 
+    pub fn components(&self) -> Vec<&dyn Component> {
+        let mut components: Vec<&dyn Component> = vec![&self.scheduling, &self.compression];
+        components.extend(self.preprocessed.components());
+        components
+    }
+
+    /// Track the values added to each relation, and report the ones that do not balance.
     pub fn track_relations<MC: MerkleChannel>(
         &self,
         commitment_scheme: &CommitmentSchemeProver<'_, SimdBackend, MC>,
-    ) -> RelationSummary
+        relations: &Relations,
+        public_data: &PublicData,
+    ) -> Diagnostics
     where
         SimdBackend: BackendForChannel<MC>,
     {
@@ -184,14 +229,35 @@ impl Components {
         let evals = &evals.as_ref();
         let trace = &evals.into();
 
-        let entries: Vec<RelationTrackerEntry> = itertools::chain!(
-            add_to_relation_entries(&self.scheduling, trace),
-            add_to_relation_entries(&self.compression, trace),
+        let mut entries = vec![
+            ComponentEntries {
+                component: "scheduling".to_string(),
+                log_size: self.scheduling.log_size(),
+                entries: add_to_relation_entries(&self.scheduling, trace),
+            },
+            ComponentEntries {
+                component: "compression".to_string(),
+                log_size: self.compression.log_size(),
+                entries: add_to_relation_entries(&self.compression, trace),
+            },
+        ];
+        entries.extend(self.preprocessed.relation_entries(trace));
+
+        Diagnostics::new(
+            entries,
+            public_data.relation_entries(relations, self.scheduling.log_size()),
+            &relations.names(),
         )
-        .chain(self.preprocessed.relation_entries(trace))
-        .collect();
+    }
 
-        RelationSummary::summarize_relations(&entries).cleaned()
+    /// Statistics of each component, see [`crate::stats`].
+    pub fn stats(&self, relation_paths: &HashMap<&'static str, String>) -> Vec<ComponentStats> {
+        let mut stats = vec![
+            ComponentStats::new("scheduling", &self.scheduling, relation_paths),
+            ComponentStats::new("compression", &self.compression, relation_paths),
+        ];
+        stats.extend(self.preprocessed.stats(relation_paths));
+        stats
     }
 
     pub fn trace_log_degree_bounds(&self) -> Vec<TreeVec<ColumnVec<u32>>> {
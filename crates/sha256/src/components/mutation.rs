@@ -0,0 +1,204 @@
+//! Soundness checks of the AIR components, by mutation of valid traces.
+//!
+//! Each mutation adds one to a single cell of the main trace, and the interaction trace is
+//! regenerated from the mutated trace, as a dishonest prover would. A sound component then either
+//! fails its constraints or changes the values it adds to the relations, so that its claimed sum
+//! no longer cancels out against the other components. A mutation going through both is an
+//! under-constrained column, such as a limb missing its range check.
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use itertools::Itertools;
+use num_traits::{One, Zero};
+use stwo::{
+    core::{
+        fields::{m31::BaseField, qm31::SecureField},
+        pcs::TreeVec,
+    },
+    prover::{
+        backend::{simd::SimdBackend, Column},
+        poly::{
+            circle::{CircleEvaluation, CirclePoly},
+            BitReversedOrder,
+        },
+    },
+};
+use stwo_constraint_framework::{
+    relation_tracker::add_to_relation_entries, FrameworkComponent, FrameworkEval,
+    TraceLocationAllocator,
+};
+
+/// Index of the main trace in the trees.
+const MAIN_TREE: usize = 1;
+
+/// Relation and values of a use of a relation.
+type RelationValue = (String, Vec<u32>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub column: usize,
+    pub row: usize,
+}
+
+impl Mutation {
+    /// Mutation of `column`, at a row spread over the traces of `n_rows` rows.
+    pub fn spread(column: usize, n_rows: usize) -> Self {
+        Self {
+            column,
+            row: column % n_rows,
+        }
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {} at row {}", self.column, self.row)
+    }
+}
+
+/// Valid preprocessed, main and interaction traces of a component, with their polynomials and the
+/// uses of the relations they make.
+pub struct MutationHarness<E: FrameworkEval> {
+    component: FrameworkComponent<E>,
+    traces: TreeVec<Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
+    polys: TreeVec<Vec<CirclePoly<SimdBackend>>>,
+    uses: Vec<(RelationValue, BaseField)>,
+}
+
+impl<E: FrameworkEval> MutationHarness<E> {
+    pub fn new(
+        eval: E,
+        traces: TreeVec<Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
+    ) -> Self {
+        let component = FrameworkComponent::new(
+            &mut TraceLocationAllocator::default(),
+            eval,
+            SecureField::zero(),
+        );
+        let polys = traces
+            .as_ref()
+            .map(|trace| trace.iter().map(|c| c.clone().interpolate()).collect_vec());
+        let uses = relation_uses(&component, &traces);
+        Self {
+            component,
+            traces,
+            polys,
+            uses,
+        }
+    }
+
+    /// Mutate one cell of each of `columns` of the main trace, and return the mutations neither
+    /// changing the sum of the multiplicities of a relation value nor failing the constraints.
+    ///
+    /// When the uses of the relations are unchanged, so is the regenerated interaction trace, and
+    /// `fails` tells whether the constraints fail on the polynomials of the mutated traces. The
+    /// rows of the mutations are spread over the trace.
+    pub fn undetected(
+        &self,
+        columns: impl IntoIterator<Item = usize>,
+        fails: impl Fn(&TreeVec<Vec<CirclePoly<SimdBackend>>>) -> bool,
+    ) -> Vec<Mutation> {
+        let main_trace = &self.traces[MAIN_TREE];
+        let sums = balance(&self.uses);
+        columns
+            .into_iter()
+            .map(|column| Mutation::spread(column, main_trace[column].len()))
+            .filter(|&mutation| {
+                let mut column = main_trace[mutation.column].clone();
+                let value = column.values.at(mutation.row);
+                column.values.set(mutation.row, value + BaseField::one());
+
+                let mut traces = self.traces.clone();
+                traces[MAIN_TREE][mutation.column] = column.clone();
+                let uses = relation_uses(&self.component, &traces);
+                if balance(&uses) != sums {
+                    return false;
+                }
+                if uses != self.uses {
+                    // The same values in another order give another interaction trace, which
+                    // could satisfy the constraints
+                    return true;
+                }
+
+                let mut polys = self.polys.clone();
+                polys[MAIN_TREE][mutation.column] = column.interpolate();
+                !fails(&polys)
+            })
+            .collect()
+    }
+}
+
+/// Uses of the relations by `component`, with their multiplicity, in the order of the relation
+/// tracker.
+fn relation_uses<E: FrameworkEval>(
+    component: &FrameworkComponent<E>,
+    traces: &TreeVec<Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
+) -> Vec<(RelationValue, BaseField)> {
+    let evals = traces
+        .as_ref()
+        .map(|trace| trace.iter().map(|c| c.values.to_cpu()).collect_vec());
+    let evals = &evals.as_ref();
+    add_to_relation_entries(component, &evals.into())
+        .into_iter()
+        .map(|entry| {
+            let values = entry.values.iter().map(|value| value.0).collect();
+            ((entry.relation, values), entry.mult)
+        })
+        .collect()
+}
+
+/// Sum of the multiplicities of each relation value, leaving out the balanced ones.
+fn balance(uses: &[(RelationValue, BaseField)]) -> HashMap<RelationValue, BaseField> {
+    let mut balance = HashMap::<RelationValue, BaseField>::new();
+    for (value, mult) in uses {
+        *balance.entry(value.clone()).or_insert_with(BaseField::zero) += *mult;
+    }
+    balance.retain(|_, mult| !mult.is_zero());
+    balance
+}
+
+/// Columns to mutate: all of them with the `slow-tests` feature, else the ones of `sample`.
+pub fn columns(n_columns: usize, sample: impl IntoIterator<Item = usize>) -> Vec<usize> {
+    if cfg!(feature = "slow-tests") {
+        (0..n_columns).collect()
+    } else {
+        sample.into_iter().filter(|&c| c < n_columns).collect()
+    }
+}
+
+/// Whether `f` panics, such as a failed `assert_constraints_on_polys`, without printing the
+/// panic.
+pub fn panics(f: impl FnOnce()) -> bool {
+    thread_local! {
+        static QUIET: Cell<bool> = const { Cell::new(false) };
+    }
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                hook(info);
+            }
+        }));
+    });
+
+    QUIET.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.set(false);
+    result.is_err()
+}
+
+/// Fail with the list of the under-constrained columns, if any.
+pub fn assert_detected(component: &str, undetected: &[Mutation]) {
+    assert!(
+        undetected.is_empty(),
+        "Under-constrained columns of {component}: {}",
+        undetected.iter().join(", ")
+    );
+}
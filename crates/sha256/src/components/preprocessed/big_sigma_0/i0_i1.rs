@@ -0,0 +1,94 @@
+use crate::{
+    partitions::{pext_u32x16, BigSigma0},
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: big_sigma_0::BigSigma0I0I1Columns[0],
+    log_size: BigSigma0::I0.count_ones(),
+    lookups: compression,
+    relations: [
+        i0_mult: big_sigma_0.i0(i0_low, i0_high_0, i0_high_1, o0_low, o0_high, o20_pext)
+            from |a_i0_low, a_i0_high_0, a_i0_high_1| {
+                pext_u32x16(a_i0_low + (a_i0_high_0 << 16) + (a_i0_high_1 << 24), BigSigma0::I0)
+            },
+        i1_mult: big_sigma_0.i1(i1_low_0, i1_low_1, i1_high, o1_low, o1_high, o21_pext)
+            from |a_i1_low_0, a_i1_low_1, a_i1_high| {
+                pext_u32x16(a_i1_low_0 + (a_i1_low_1 << 8) + (a_i1_high << 16), BigSigma0::I1)
+            },
+    ],
+}
+
+#[cfg(test)]
+mod tests {
+    use std::simd::u32x16;
+
+    use stwo::prover::backend::simd::m31::LOG_N_LANES;
+
+    use super::*;
+    use crate::{
+        components::{
+            compression::witness::gen_trace as gen_compression_trace,
+            scheduling::witness::gen_trace as gen_scheduling_trace,
+        },
+        partitions::SubsetIterator,
+        preprocessed::big_sigma_0,
+    };
+
+    #[test]
+    fn test_value_at_index() {
+        let preprocessed_cols = big_sigma_0::gen_column_simd();
+
+        let mut iterator = SubsetIterator::new(BigSigma0::I0);
+
+        let x: [u32; 16] = std::array::from_fn(|_| iterator.next().unwrap());
+        let x_low = u32x16::from_slice(&x.map(|x| x & BigSigma0::I0_L));
+        let x_high_0 = u32x16::from_slice(&x.map(|x| (x >> 16) & BigSigma0::I0_H0));
+        let x_high_1 = u32x16::from_slice(&x.map(|x| (x >> 24) & BigSigma0::I0_H1));
+        let idx_i0 = pext_u32x16(x_low + (x_high_0 << 16) + (x_high_1 << 32), BigSigma0::I0);
+
+        assert_eq!(
+            idx_i0
+                .to_array()
+                .iter()
+                .map(|x| {
+                    preprocessed_cols[0][(*x >> LOG_N_LANES) as usize].to_array()
+                        [(*x % (1 << LOG_N_LANES)) as usize]
+                })
+                .collect::<Vec<u32>>(),
+            x_low.to_array().to_vec()
+        );
+        assert_eq!(
+            idx_i0
+                .to_array()
+                .iter()
+                .map(|x| {
+                    preprocessed_cols[1][(*x >> LOG_N_LANES) as usize].to_array()
+                        [(*x % (1 << LOG_N_LANES)) as usize]
+                })
+                .collect::<Vec<u32>>(),
+            x_high_0.to_array().to_vec()
+        );
+        assert_eq!(
+            idx_i0
+                .to_array()
+                .iter()
+                .map(|x| {
+                    preprocessed_cols[2][(*x >> LOG_N_LANES) as usize].to_array()
+                        [(*x % (1 << LOG_N_LANES)) as usize]
+                })
+                .collect::<Vec<u32>>(),
+            x_high_1.to_array().to_vec()
+        );
+    }
+
+    #[test_log::test]
+    fn test_trace() {
+        const LOG_N_SHA256: u32 = 8;
+        let log_size = 21;
+        let (scheduling_trace, scheduling_lookup_data) = gen_scheduling_trace(LOG_N_SHA256);
+        let (_, compression_lookup_data) = gen_compression_trace(&scheduling_trace);
+        let trace = gen_trace(log_size, &scheduling_lookup_data, &compression_lookup_data);
+        assert!(trace.iter().all(|t| t.len() == trace[0].len()));
+    }
+}
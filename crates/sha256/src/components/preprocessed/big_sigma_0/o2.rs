@@ -0,0 +1,15 @@
+use crate::{
+    partitions::BigSigma0, preprocessed::big_sigma_0::BigSigma0I0I1Columns, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: big_sigma_0::BigSigma0O2Columns[BigSigma0I0I1Columns::SIZE],
+    log_size: BigSigma0::O2.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        o2_mult: big_sigma_0.o2(o2_0, o2_1, o2_low, o2_high)
+            from |sigma_0_o20_pext, sigma_0_o21_pext| {
+                (sigma_0_o20_pext << BigSigma0::O2.count_ones()) + sigma_0_o21_pext
+            },
+    ],
+}
@@ -0,0 +1,14 @@
+use crate::{
+    partitions::{pext_u32x16, BigSigma1},
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: big_sigma_1::BigSigma1I0Columns[0],
+    log_size: BigSigma1::I0.count_ones(),
+    lookups: compression,
+    relations: [
+        i0_mult: big_sigma_1.i0(i0_low, i0_high, o0_low, o0_high, o20_pext)
+            from |e_i0_low, e_i0_high| pext_u32x16(e_i0_low + (e_i0_high << 16), BigSigma1::I0),
+    ],
+}
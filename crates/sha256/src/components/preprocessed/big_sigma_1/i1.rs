@@ -0,0 +1,15 @@
+use crate::{
+    partitions::{pext_u32x16, BigSigma1},
+    preprocessed::big_sigma_1::BigSigma1I0Columns,
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: big_sigma_1::BigSigma1I1Columns[BigSigma1I0Columns::SIZE],
+    log_size: BigSigma1::I1.count_ones(),
+    lookups: compression,
+    relations: [
+        i1_mult: big_sigma_1.i1(i1_low, i1_high, o1_low, o1_high, o21_pext)
+            from |e_i1_low, e_i1_high| pext_u32x16(e_i1_low + (e_i1_high << 16), BigSigma1::I1),
+    ],
+}
@@ -1,3 +0,0 @@
-use utils::trace_columns;
-
-trace_columns!(ComponentColumns, i1_mult);
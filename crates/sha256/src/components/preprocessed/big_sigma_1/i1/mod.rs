@@ -1,3 +0,0 @@
-pub mod air;
-pub mod columns;
-pub mod witness;
@@ -0,0 +1,17 @@
+use crate::{
+    partitions::BigSigma1,
+    preprocessed::big_sigma_1::{BigSigma1I0Columns, BigSigma1I1Columns},
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: big_sigma_1::BigSigma1O2Columns[BigSigma1I0Columns::SIZE + BigSigma1I1Columns::SIZE],
+    log_size: BigSigma1::O2.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        o2_mult: big_sigma_1.o2(o2_0, o2_1, o2_low, o2_high)
+            from |sigma_1_o20_pext, sigma_1_o21_pext| {
+                (sigma_1_o20_pext << BigSigma1::O2.count_ones()) + sigma_1_o21_pext
+            },
+    ],
+}
@@ -0,0 +1,15 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma1, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: ch_left::ChLeftI0Columns[0],
+    log_size: BigSigma1::I0_L.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        i0_low_mult: ch_left.i0_low(i0_low_e, i0_low_f, i0_low_res)
+            from |e_i0_low, f_i0_low| pext_index([e_i0_low, f_i0_low], BigSigma1::I0_L),
+        i0_high_mult: ch_left.i0_high(i0_high_e, i0_high_f, i0_high_res)
+            from |e_i0_high, f_i0_high| pext_index([e_i0_high, f_i0_high], BigSigma1::I0_H),
+    ],
+}
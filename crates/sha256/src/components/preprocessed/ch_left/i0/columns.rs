@@ -1,3 +0,0 @@
-use utils::trace_columns;
-
-trace_columns!(ComponentColumns, i0_low_mult, i0_high_mult);
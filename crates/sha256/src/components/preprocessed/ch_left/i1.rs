@@ -0,0 +1,16 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma1,
+    preprocessed::ch_left::ChLeftI0Columns, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: ch_left::ChLeftI1Columns[ChLeftI0Columns::SIZE],
+    log_size: BigSigma1::I1_L.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        i1_low_mult: ch_left.i1_low(i1_low_e, i1_low_f, i1_low_res)
+            from |e_i1_low, f_i1_low| pext_index([e_i1_low, f_i1_low], BigSigma1::I1_L),
+        i1_high_mult: ch_left.i1_high(i1_high_e, i1_high_f, i1_high_res)
+            from |e_i1_high, f_i1_high| pext_index([e_i1_high, f_i1_high], BigSigma1::I1_H),
+    ],
+}
@@ -0,0 +1,15 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma1, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: ch_right::ChRightI0Columns[0],
+    log_size: BigSigma1::I0_L.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        i0_low_mult: ch_right.i0_low(i0_low_e, i0_low_g, i0_low_res)
+            from |e_i0_low, g_i0_low| pext_index([e_i0_low, g_i0_low], BigSigma1::I0_L),
+        i0_high_mult: ch_right.i0_high(i0_high_e, i0_high_g, i0_high_res)
+            from |e_i0_high, g_i0_high| pext_index([e_i0_high, g_i0_high], BigSigma1::I0_H),
+    ],
+}
@@ -0,0 +1,16 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma1,
+    preprocessed::ch_right::ChRightI0Columns, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: ch_right::ChRightI1Columns[ChRightI0Columns::SIZE],
+    log_size: BigSigma1::I1_L.count_ones() * 2,
+    lookups: compression,
+    relations: [
+        i1_low_mult: ch_right.i1_low(i1_low_e, i1_low_g, i1_low_res)
+            from |e_i1_low, g_i1_low| pext_index([e_i1_low, g_i1_low], BigSigma1::I1_L),
+        i1_high_mult: ch_right.i1_high(i1_high_e, i1_high_g, i1_high_res)
+            from |e_i1_high, g_i1_high| pext_index([e_i1_high, g_i1_high], BigSigma1::I1_H),
+    ],
+}
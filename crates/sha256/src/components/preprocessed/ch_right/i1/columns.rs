@@ -1,3 +0,0 @@
-use utils::trace_columns;
-
-trace_columns!(ComponentColumns, i1_low_mult, i1_high_mult);
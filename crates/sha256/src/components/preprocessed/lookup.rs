@@ -0,0 +1,145 @@
+//! Trace generation shared by the components declared with [`crate::preprocessed_component`].
+//!
+//! A component holds one multiplicity column per relation and per chunk of its table, the
+//! relations interleaved for each chunk. The logup columns sum these multiplicities in pairs.
+
+use std::simd::u32x16;
+
+use itertools::Itertools;
+use stwo::{
+    core::{
+        fields::{m31::BaseField, qm31::QM31},
+        ColumnVec,
+    },
+    prover::{
+        backend::simd::{
+            m31::{PackedM31, LOG_N_LANES},
+            qm31::PackedQM31,
+            SimdBackend,
+        },
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+    },
+};
+use stwo_constraint_framework::LogupTraceGenerator;
+use utils::{simd::into_simd, write_col, write_pair};
+
+use crate::{
+    components::{
+        compression::columns::{
+            FeedForwardColumns, RoundInteractionColumns as CompressionInteractionColumns,
+        },
+        scheduling::columns::RoundInteractionColumns as SchedulingInteractionColumns,
+        W_SIZE,
+    },
+    partitions::pext_u32x16,
+    sha256::{H, N_COMPRESSION_ROUNDS, N_SCHEDULING_ROUNDS},
+};
+
+/// Lookup columns of each scheduling round.
+pub fn scheduling_rounds(
+    lookup_data: &[Vec<u32x16>],
+) -> impl Iterator<Item = SchedulingInteractionColumns<'_, Vec<u32x16>>> {
+    (0..N_SCHEDULING_ROUNDS).map(move |round| {
+        let start = W_SIZE + round * SchedulingInteractionColumns::SIZE;
+        let end = start + SchedulingInteractionColumns::SIZE;
+        SchedulingInteractionColumns::from_slice(&lookup_data[start..end])
+    })
+}
+
+/// Lookup columns of each compression round.
+pub fn compression_rounds(
+    lookup_data: &[Vec<u32x16>],
+) -> impl Iterator<Item = CompressionInteractionColumns<'_, Vec<u32x16>>> {
+    (0..N_COMPRESSION_ROUNDS).map(move |round| {
+        let start = W_SIZE + round * CompressionInteractionColumns::SIZE;
+        let end = start + CompressionInteractionColumns::SIZE;
+        CompressionInteractionColumns::from_slice(&lookup_data[start..end])
+    })
+}
+
+/// Lookup columns of the feed-forward of each word of the state.
+pub fn feed_forward_rounds(
+    lookup_data: &[Vec<u32x16>],
+) -> impl Iterator<Item = FeedForwardColumns<'_, Vec<u32x16>>> {
+    (0..H.len()).map(move |i| {
+        let start = W_SIZE
+            + N_COMPRESSION_ROUNDS * CompressionInteractionColumns::SIZE
+            + i * FeedForwardColumns::SIZE;
+        let end = start + FeedForwardColumns::SIZE;
+        FeedForwardColumns::from_slice(&lookup_data[start..end])
+    })
+}
+
+/// Index in a table enumerating the bits of `mask` of each value, the first value being the most
+/// significant.
+pub fn pext_index<const N: usize>(values: [u32x16; N], mask: u32) -> u32x16 {
+    values.into_iter().fold(u32x16::splat(0), |index, value| {
+        (index << mask.count_ones()) + pext_u32x16(value, mask)
+    })
+}
+
+/// Split the multiplicities of each relation in columns of `log_size`, interleaving the relations
+/// for each chunk.
+pub fn split_multiplicities<const N: usize>(
+    log_size: u32,
+    multiplicities: [Vec<u32>; N],
+) -> Vec<Vec<u32x16>> {
+    let chunk_size = 1 << (log_size - LOG_N_LANES);
+    let chunks = multiplicities
+        .each_ref()
+        .map(|m| into_simd(m).chunks(chunk_size).collect_vec());
+    assert!(
+        chunks.iter().map(Vec::len).all_equal(),
+        "relations over tables of different sizes"
+    );
+
+    (0..chunks[0].len())
+        .flat_map(|i| chunks.iter().map(move |c| c[i].to_vec()))
+        .collect()
+}
+
+/// Logup columns of the multiplicities of [`split_multiplicities`] over the combined table rows of
+/// each relation, summed in pairs.
+pub fn gen_interaction_trace<const N: usize>(
+    trace: &[Vec<u32x16>],
+    denominators: [Vec<PackedQM31>; N],
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    QM31,
+) {
+    let simd_size = trace[0].len();
+    let log_size = simd_size.ilog2() + LOG_N_LANES;
+    let mut interaction_trace = LogupTraceGenerator::new(log_size);
+
+    let chunks = denominators
+        .each_ref()
+        .map(|d| d.chunks(simd_size).collect_vec());
+    let columns = trace
+        .iter()
+        .zip_eq((0..chunks[0].len()).flat_map(|i| chunks.iter().map(move |c| c[i])))
+        .collect_vec();
+    let numerators = |mult: &Vec<u32x16>| {
+        mult.iter()
+            .map(|v| unsafe { PackedM31::from_simd_unchecked(*v) })
+            .map(PackedQM31::from)
+            .collect_vec()
+    };
+
+    for pair in columns.chunks(2) {
+        match pair {
+            [(mult_0, den_0), (mult_1, den_1)] => write_pair!(
+                numerators(mult_0),
+                den_0.to_vec(),
+                numerators(mult_1),
+                den_1.to_vec(),
+                interaction_trace
+            ),
+            [(mult, den)] => {
+                write_col!(numerators(mult), den.to_vec(), interaction_trace);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    interaction_trace.finalize_last()
+}
@@ -0,0 +1,20 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma0,
+    preprocessed::maj::MajI0LI1HColumns, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: maj::MajI0H0I1L0Columns[MajI0LI1HColumns::SIZE],
+    log_size: BigSigma0::I0_H0.count_ones() * 3,
+    lookups: compression,
+    relations: [
+        i0_high_0_mult: maj.i0_high_0(i0_high_0_a, i0_high_0_b, i0_high_0_c, i0_high_0_res)
+            from |a_i0_high_0, b_i0_high_0, c_i0_high_0| {
+                pext_index([a_i0_high_0, b_i0_high_0, c_i0_high_0], BigSigma0::I0_H0)
+            },
+        i1_low_0_mult: maj.i1_low_0(i1_low_0_a, i1_low_0_b, i1_low_0_c, i1_low_0_res)
+            from |a_i1_low_0, b_i1_low_0, c_i1_low_0| {
+                pext_index([a_i1_low_0, b_i1_low_0, c_i1_low_0], BigSigma0::I1_L0)
+            },
+    ],
+}
@@ -0,0 +1,22 @@
+use crate::{
+    components::preprocessed::lookup::pext_index,
+    partitions::BigSigma0,
+    preprocessed::maj::{MajI0H0I1L0Columns, MajI0LI1HColumns},
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: maj::MajI0H1I1L1Columns[MajI0LI1HColumns::SIZE + MajI0H0I1L0Columns::SIZE],
+    log_size: BigSigma0::I0_H1.count_ones() * 3,
+    lookups: compression,
+    relations: [
+        i0_high_1_mult: maj.i0_high_1(i0_high_1_a, i0_high_1_b, i0_high_1_c, i0_high_1_res)
+            from |a_i0_high_1, b_i0_high_1, c_i0_high_1| {
+                pext_index([a_i0_high_1, b_i0_high_1, c_i0_high_1], BigSigma0::I0_H1)
+            },
+        i1_low_1_mult: maj.i1_low_1(i1_low_1_a, i1_low_1_b, i1_low_1_c, i1_low_1_res)
+            from |a_i1_low_1, b_i1_low_1, c_i1_low_1| {
+                pext_index([a_i1_low_1, b_i1_low_1, c_i1_low_1], BigSigma0::I1_L1)
+            },
+    ],
+}
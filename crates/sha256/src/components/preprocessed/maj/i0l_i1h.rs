@@ -0,0 +1,19 @@
+use crate::{
+    components::preprocessed::lookup::pext_index, partitions::BigSigma0, preprocessed_component,
+};
+
+preprocessed_component! {
+    table: maj::MajI0LI1HColumns[0],
+    log_size: BigSigma0::I0_L.count_ones() * 3,
+    lookups: compression,
+    relations: [
+        i0_low_mult: maj.i0_low(i0_low_a, i0_low_b, i0_low_c, i0_low_res)
+            from |a_i0_low, b_i0_low, c_i0_low| {
+                pext_index([a_i0_low, b_i0_low, c_i0_low], BigSigma0::I0_L)
+            },
+        i1_high_mult: maj.i1_high(i1_high_a, i1_high_b, i1_high_c, i1_high_res)
+            from |a_i1_high, b_i1_high, c_i1_high| {
+                pext_index([a_i1_high, b_i1_high, c_i1_high], BigSigma0::I1_H)
+            },
+    ],
+}
@@ -2,6 +2,7 @@ mod big_sigma_0;
 mod big_sigma_1;
 mod ch_left;
 mod ch_right;
+pub mod lookup;
 mod maj;
 mod range_check_add;
 mod sigma_0;
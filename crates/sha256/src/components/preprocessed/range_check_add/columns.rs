@@ -1,3 +0,0 @@
-use utils::trace_columns;
-
-trace_columns!(ComponentColumns, carry_4_mult, carry_7_mult, carry_8_mult);
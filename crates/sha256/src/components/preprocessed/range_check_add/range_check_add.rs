@@ -0,0 +1,22 @@
+use crate::preprocessed_component;
+
+preprocessed_component! {
+    table: range_check_add::RangeCheckAddColumns[0],
+    log_size: 19,
+    relations: [
+        carry_4_mult: range_check_add.add_4(value, carry_4) from [
+            scheduling |new_w_low, carry_low| (new_w_low << 3) + carry_low,
+            scheduling |new_w_high, carry_high| (new_w_high << 3) + carry_high,
+            feed_forward |out_low, carry_low| (out_low << 3) + carry_low,
+            feed_forward |out_high, carry_high| (out_high << 3) + carry_high,
+        ],
+        carry_7_mult: range_check_add.add_7(value, carry_7) from [
+            compression |new_e_low, e_carry_low| (new_e_low << 3) + e_carry_low,
+            compression |new_e_high, e_carry_high| (new_e_high << 3) + e_carry_high,
+        ],
+        carry_8_mult: range_check_add.add_8(value, carry_8) from [
+            compression |new_a_low, a_carry_low| (new_a_low << 3) + a_carry_low,
+            compression |new_a_high, a_carry_high| (new_a_high << 3) + a_carry_high,
+        ],
+    ],
+}
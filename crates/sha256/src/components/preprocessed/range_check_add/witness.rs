@@ -1,165 +0,0 @@
-use std::simd::u32x16;
-
-use itertools::izip;
-use stwo::{
-    core::{
-        fields::{m31::BaseField, qm31::QM31},
-        ColumnVec,
-    },
-    prover::{
-        backend::simd::{
-            m31::{PackedM31, LOG_N_LANES},
-            qm31::PackedQM31,
-            SimdBackend,
-        },
-        poly::{circle::CircleEvaluation, BitReversedOrder},
-    },
-};
-use stwo_constraint_framework::{LogupTraceGenerator, Relation};
-use utils::{aligned_vec, combine, simd::into_simd, write_col, write_pair};
-
-use crate::{
-    components::{
-        compression::columns::RoundInteractionColumns as CompressionInteractionColumns,
-        scheduling::columns::RoundInteractionColumns as SchedulingInteractionColumns, W_SIZE,
-    },
-    preprocessed::range_check_add::{self, RangeCheckAddColumns},
-    relations::Relations,
-    sha256::{N_COMPRESSION_ROUNDS, N_SCHEDULING_ROUNDS},
-};
-
-pub fn gen_trace(
-    log_size: u32,
-    scheduling_lookup_data: &[Vec<u32x16>],
-    compression_lookup_data: &[Vec<u32x16>],
-) -> Vec<Vec<u32x16>> {
-    // Dense counters for each relation
-    let mut carry_4_mult = aligned_vec![0u32; 1 << 19];
-    let mut carry_7_mult = aligned_vec![0u32; 1 << 19];
-    let mut carry_8_mult = aligned_vec![0u32; 1 << 19];
-
-    // Aggregate over all scheduling lookups
-    for round in 0..N_SCHEDULING_ROUNDS {
-        let start = W_SIZE + round * SchedulingInteractionColumns::SIZE;
-        let end = start + SchedulingInteractionColumns::SIZE;
-
-        let cols = SchedulingInteractionColumns::from_slice(&scheduling_lookup_data[start..end]);
-
-        izip!(cols.new_w_low, cols.carry_low).for_each(|(new_w_low, carry_low)| {
-            let idx = (new_w_low << 3) + carry_low;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_4_mult[*x as usize] += 1);
-        });
-        izip!(cols.new_w_high, cols.carry_high).for_each(|(new_w_high, carry_high)| {
-            let idx = (new_w_high << 3) + carry_high;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_4_mult[*x as usize] += 1);
-        });
-    }
-
-    // Aggregate over all compression lookups
-    for round in 0..N_COMPRESSION_ROUNDS {
-        let start = W_SIZE + round * CompressionInteractionColumns::SIZE;
-        let end = start + CompressionInteractionColumns::SIZE;
-
-        let cols = CompressionInteractionColumns::from_slice(&compression_lookup_data[start..end]);
-
-        izip!(cols.new_e_low, cols.e_carry_low).for_each(|(new_e_low, e_carry_low)| {
-            let idx = (new_e_low << 3) + e_carry_low;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_7_mult[*x as usize] += 1);
-        });
-        izip!(cols.new_e_high, cols.e_carry_high).for_each(|(new_e_high, e_carry_high)| {
-            let idx = (new_e_high << 3) + e_carry_high;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_7_mult[*x as usize] += 1);
-        });
-        izip!(cols.new_a_low, cols.a_carry_low).for_each(|(new_a_low, a_carry_low)| {
-            let idx = (new_a_low << 3) + a_carry_low;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_8_mult[*x as usize] += 1);
-        });
-        izip!(cols.new_a_high, cols.a_carry_high).for_each(|(new_a_high, a_carry_high)| {
-            let idx = (new_a_high << 3) + a_carry_high;
-            idx.to_array()
-                .iter()
-                .for_each(|x| carry_8_mult[*x as usize] += 1);
-        });
-    }
-
-    into_simd(&carry_4_mult)
-        .chunks((1 << (log_size - LOG_N_LANES)) as usize)
-        .zip(into_simd(&carry_7_mult).chunks((1 << (log_size - LOG_N_LANES)) as usize))
-        .zip(into_simd(&carry_8_mult).chunks((1 << (log_size - LOG_N_LANES)) as usize))
-        .flat_map(|((c4, c7), c8)| [c4.to_vec(), c7.to_vec(), c8.to_vec()])
-        .collect()
-}
-
-pub fn gen_interaction_trace(
-    trace: &[Vec<u32x16>],
-    relations: &Relations,
-) -> (
-    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
-    QM31,
-) {
-    let preprocessed_columns = range_check_add::gen_column_simd();
-    let RangeCheckAddColumns {
-        value,
-        carry_4,
-        carry_7,
-        carry_8,
-    } = RangeCheckAddColumns::from_slice(&preprocessed_columns[..]);
-
-    let simd_size = trace[0].len();
-    let log_size = simd_size.ilog2() + LOG_N_LANES;
-    let mut interaction_trace = LogupTraceGenerator::new(log_size);
-
-    let add_4_den = combine!(relations.range_check_add.add_4, [&value, &carry_4]);
-    let add_7_den = combine!(relations.range_check_add.add_7, [&value, &carry_7]);
-    let add_8_den = combine!(relations.range_check_add.add_8, [&value, &carry_8]);
-    let dens = [add_4_den, add_7_den, add_8_den];
-
-    for (i, [mult_0, mult_1]) in trace.array_chunks::<2>().enumerate() {
-        let den_0 = &dens[(2 * i) % 3]
-            .chunks(simd_size)
-            .nth((2 * i) / 3)
-            .unwrap();
-        let den_1 = &dens[(2 * i + 1) % 3]
-            .chunks(simd_size)
-            .nth((2 * i + 1) / 3)
-            .unwrap();
-
-        write_pair!(
-            mult_0
-                .iter()
-                .map(|v| unsafe { PackedM31::from_simd_unchecked(*v) })
-                .map(PackedQM31::from),
-            den_0.to_vec(),
-            mult_1
-                .iter()
-                .map(|v| unsafe { PackedM31::from_simd_unchecked(*v) })
-                .map(PackedQM31::from),
-            den_1.to_vec(),
-            interaction_trace
-        );
-    }
-
-    if trace.len() % 2 == 1 {
-        let mult = trace.last().unwrap();
-        let den = dens[2].chunks(simd_size).last().unwrap();
-        write_col!(
-            mult.iter()
-                .map(|v| unsafe { PackedM31::from_simd_unchecked(*v) })
-                .map(PackedQM31::from),
-            den.to_vec(),
-            interaction_trace
-        );
-    }
-
-    interaction_trace.finalize_last()
-}
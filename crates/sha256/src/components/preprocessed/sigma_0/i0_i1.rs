@@ -0,0 +1,65 @@
+use crate::{
+    partitions::{pext_u32x16, Sigma0},
+    preprocessed_component,
+};
+
+preprocessed_component! {
+    table: sigma_0::Sigma0I0I1Columns[0],
+    log_size: Sigma0::I0.count_ones(),
+    lookups: scheduling,
+    relations: [
+        i0_mult: sigma_0.i0(i0_low, i0_high, o0_low, o0_high, o20_pext)
+            from |w_15_i0_low, w_15_i0_high| {
+                pext_u32x16(w_15_i0_low + (w_15_i0_high << 16), Sigma0::I0)
+            },
+        i1_mult: sigma_0.i1(i1_low, i1_high, o1_low, o1_high, o21_pext)
+            from |w_15_i1_low, w_15_i1_high| {
+                pext_u32x16(w_15_i1_low + (w_15_i1_high << 16), Sigma0::I1)
+            },
+    ],
+}
+
+#[cfg(test)]
+mod tests {
+    use std::simd::u32x16;
+
+    use stwo::prover::backend::simd::m31::LOG_N_LANES;
+
+    use super::*;
+    use crate::{partitions::SubsetIterator, preprocessed::sigma_0};
+
+    #[test]
+    fn test_value_at_index() {
+        let preprocessed_cols = sigma_0::gen_column_simd();
+
+        let mut iterator = SubsetIterator::new(Sigma0::I0);
+
+        let x: [u32; 16] = std::array::from_fn(|_| iterator.next().unwrap());
+        let x_low = u32x16::from_slice(&x.map(|x| x & Sigma0::I0_L));
+        let x_high = u32x16::from_slice(&x.map(|x| (x >> 16) & Sigma0::I0_H));
+        let idx_i0 = pext_u32x16(x_low + (x_high << 16), Sigma0::I0);
+
+        assert_eq!(
+            idx_i0
+                .to_array()
+                .iter()
+                .map(|x| {
+                    preprocessed_cols[0][(*x >> LOG_N_LANES) as usize].to_array()
+                        [(*x % (1 << LOG_N_LANES)) as usize]
+                })
+                .collect::<Vec<u32>>(),
+            x_low.to_array().to_vec()
+        );
+        assert_eq!(
+            idx_i0
+                .to_array()
+                .iter()
+                .map(|x| {
+                    preprocessed_cols[1][(*x >> LOG_N_LANES) as usize].to_array()
+                        [(*x % (1 << LOG_N_LANES)) as usize]
+                })
+                .collect::<Vec<u32>>(),
+            x_high.to_array().to_vec()
+        );
+    }
+}
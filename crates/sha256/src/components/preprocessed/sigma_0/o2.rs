@@ -0,0 +1,13 @@
+use crate::{partitions::Sigma0, preprocessed::sigma_0::Sigma0I0I1Columns, preprocessed_component};
+
+preprocessed_component! {
+    table: sigma_0::Sigma0O2Columns[Sigma0I0I1Columns::SIZE],
+    log_size: Sigma0::O2.count_ones() * 2,
+    lookups: scheduling,
+    relations: [
+        o2_mult: sigma_0.o2(o2_0, o2_1, o2_low, o2_high)
+            from |sigma_0_o20_pext, sigma_0_o21_pext| {
+                (sigma_0_o20_pext << Sigma0::O2.count_ones()) + sigma_0_o21_pext
+            },
+    ],
+}
@@ -1,3 +0,0 @@
-use utils::trace_columns;
-
-trace_columns!(ComponentColumns, sigma_1_i0_mult, sigma_1_i1_mult);
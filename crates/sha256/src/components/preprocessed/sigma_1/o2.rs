@@ -0,0 +1,13 @@
+use crate::{partitions::Sigma1, preprocessed::sigma_1::Sigma1I0I1Columns, preprocessed_component};
+
+preprocessed_component! {
+    table: sigma_1::Sigma1O2Columns[Sigma1I0I1Columns::SIZE],
+    log_size: Sigma1::O2.count_ones() * 2,
+    lookups: scheduling,
+    relations: [
+        o2_mult: sigma_1.o2(o2_0, o2_1, o2_low, o2_high)
+            from |sigma_1_o20_pext, sigma_1_o21_pext| {
+                (sigma_1_o20_pext << Sigma1::O2.count_ones()) + sigma_1_o21_pext
+            },
+    ],
+}
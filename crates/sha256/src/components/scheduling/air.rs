@@ -171,11 +171,20 @@ impl FrameworkEval for Eval {
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
-    use stwo::core::{pcs::TreeVec, poly::circle::CanonicCoset};
+    use stwo::{
+        core::{pcs::TreeVec, poly::circle::CanonicCoset},
+        prover::backend::simd::m31::LOG_N_LANES,
+    };
     use stwo_constraint_framework::assert_constraints_on_polys;
 
     use super::*;
-    use crate::components::scheduling::witness::{gen_interaction_trace, gen_trace};
+    use crate::components::{
+        mutation::{self, MutationHarness},
+        scheduling::{
+            columns::RoundColumns,
+            witness::{gen_interaction_trace, gen_trace},
+        },
+    };
 
     #[test]
     fn test_scheduling_constraints() {
@@ -200,4 +209,38 @@ mod tests {
             claimed_sum,
         );
     }
+
+    /// A cell only used in lookups changes the values added to the relations.
+    #[test]
+    fn test_scheduling_mutations() {
+        const LOG_N_ROWS: u32 = LOG_N_LANES;
+
+        let (trace, lookup_data) = gen_trace(LOG_N_ROWS);
+        let relations = Relations::dummy();
+        let (interaction_trace, claimed_sum) = gen_interaction_trace(&lookup_data, &relations);
+        let n_columns = trace.len();
+
+        let eval = Eval {
+            log_size: LOG_N_ROWS,
+            relations: relations.clone(),
+        };
+        let harness =
+            MutationHarness::new(eval, TreeVec::new(vec![vec![], trace, interaction_trace]));
+        let sample = (0..2)
+            .chain(W_SIZE..W_SIZE + RoundColumns::SIZE)
+            .chain(n_columns - RoundColumns::SIZE..n_columns);
+        let undetected = harness.undetected(mutation::columns(n_columns, sample), |polys| {
+            mutation::panics(|| {
+                assert_constraints_on_polys(
+                    polys,
+                    CanonicCoset::new(LOG_N_ROWS),
+                    |mut eval| {
+                        eval_scheduling_constraints(&mut eval, &relations);
+                    },
+                    claimed_sum,
+                )
+            })
+        });
+        mutation::assert_detected("scheduling", &undetected);
+    }
 }
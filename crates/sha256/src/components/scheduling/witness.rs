@@ -1,5 +1,6 @@
 use std::simd::u32x16;
 
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use stwo::{
     core::{
         fields::{m31::BaseField, qm31::QM31},
@@ -16,7 +17,10 @@ use stwo::{
     },
 };
 use stwo_constraint_framework::{LogupTraceGenerator, Relation};
-use utils::{combine, consume_pair, emit_col, simd::generate_simd_sequence_bulk};
+use utils::{
+    combine, consume_pair, emit_col,
+    simd::{par_chunk_size, pivot, row_chunks_mut},
+};
 
 use crate::{
     components::{
@@ -31,6 +35,19 @@ use crate::{
 const N_COLUMNS: usize = W_SIZE + RoundColumns::SIZE * N_SCHEDULING_ROUNDS;
 const N_INTERACTION_COLUMNS: usize = W_SIZE + RoundInteractionColumns::SIZE * N_SCHEDULING_ROUNDS;
 
+/// Generate synthetic message blocks, limb `k` of row `r` being `(k + r) & 0xffff`.
+pub fn gen_blocks(log_size: u32) -> Vec<[u32; 16]> {
+    (0..1u32 << log_size)
+        .map(|row| {
+            std::array::from_fn(|j| {
+                let low = (2 * j as u32 + row) & 0xffff;
+                let high = (2 * j as u32 + 1 + row) & 0xffff;
+                low + (high << 16)
+            })
+        })
+        .collect()
+}
+
 #[allow(clippy::type_complexity)]
 pub fn gen_trace(
     log_size: u32,
@@ -38,180 +55,208 @@ pub fn gen_trace(
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     Vec<Vec<u32x16>>,
 ) {
+    gen_trace_from_blocks(&gen_blocks(log_size))
+}
+
+/// Generate the scheduling trace with one message block per row.
+///
+/// The number of blocks must be a power of two, at least `N_LANES`.
+#[allow(clippy::type_complexity)]
+pub fn gen_trace_from_blocks(
+    blocks: &[[u32; 16]],
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Vec<Vec<u32x16>>,
+) {
+    assert!(blocks.len().is_power_of_two());
+    let log_size = blocks.len().ilog2();
     assert!(log_size >= LOG_N_LANES);
     let simd_size = 1 << (log_size - LOG_N_LANES);
 
-    // Initialize vec for all groups of columns
+    // Preallocate all groups of columns, filled in place
     let mut evals: Vec<Vec<u32x16>> = (0..N_COLUMNS)
-        .map(|_| Vec::with_capacity(simd_size))
+        .map(|_| vec![u32x16::splat(0); simd_size])
         .collect::<Vec<_>>();
     let mut lookup_data: Vec<Vec<u32x16>> = (0..N_INTERACTION_COLUMNS)
-        .map(|_| Vec::with_capacity(simd_size))
+        .map(|_| vec![u32x16::splat(0); simd_size])
         .collect::<Vec<_>>();
 
-    // Generate random inputs
-    evals
-        .iter_mut()
-        .enumerate()
-        .take(CHUNK_SIZE)
-        .for_each(|(i, eval)| {
-            *eval = generate_simd_sequence_bulk(i, 1 << log_size);
-        });
-    lookup_data
-        .iter_mut()
-        .enumerate()
-        .take(CHUNK_SIZE)
-        .for_each(|(i, eval)| {
-            *eval = generate_simd_sequence_bulk(i, 1 << log_size);
-        });
-
-    for t in 16..(16 + N_SCHEDULING_ROUNDS) {
-        let index = W_SIZE + (t - 16) * RoundColumns::SIZE;
-        let interaction_index = W_SIZE + (t - 16) * RoundInteractionColumns::SIZE;
-
-        for simd_row in 0..simd_size {
-            // Load the W values
-            let w_16_low = evals[2 * (t - 16)][simd_row];
-            let w_16_high = evals[2 * (t - 16) + 1][simd_row];
-            let w_15_low = evals[2 * (t - 15)][simd_row];
-            let w_15_high = evals[2 * (t - 15) + 1][simd_row];
-            let w_7_low = evals[2 * (t - 7)][simd_row];
-            let w_7_high = evals[2 * (t - 7) + 1][simd_row];
-            let w_2_low = evals[2 * (t - 2)][simd_row];
-            let w_2_high = evals[2 * (t - 2) + 1][simd_row];
-
-            // SIGMA0
-            // Decomposition over I0
-            let w_15_i0_low = w_15_low & u32x16::splat(Sigma0::I0_L);
-            let w_15_i0_high = w_15_high & u32x16::splat(Sigma0::I0_H);
-            let sigma_0 = small_sigma_0_u32x16(w_15_i0_low + (w_15_i0_high << 16));
-            let sigma_0_o0_low = sigma_0 & u32x16::splat(Sigma0::O0_L);
-            let sigma_0_o0_high = (sigma_0 >> 16) & u32x16::splat(Sigma0::O0_H);
-            let sigma_0_o20 = sigma_0 & u32x16::splat(Sigma0::O2);
-            let sigma_0_o20_pext = pext_u32x16(sigma_0_o20, Sigma0::O2);
-
-            // Decomposition over I1
-            let w_15_i1_low = w_15_low & u32x16::splat(Sigma0::I1_L);
-            let w_15_i1_high = w_15_high & u32x16::splat(Sigma0::I1_H);
-            let sigma_0 = small_sigma_0_u32x16(w_15_i1_low + (w_15_i1_high << 16));
-            let sigma_0_o1_low = sigma_0 & u32x16::splat(Sigma0::O1_L);
-            let sigma_0_o1_high = (sigma_0 >> 16) & u32x16::splat(Sigma0::O1_H);
-            let sigma_0_o21 = sigma_0 & u32x16::splat(Sigma0::O2);
-            let sigma_0_o21_pext = pext_u32x16(sigma_0_o21, Sigma0::O2);
-
-            // XOR the two O2 values
-            let sigma_0_o2 = sigma_0_o20 ^ sigma_0_o21;
-            let sigma_0_o2_low = sigma_0_o2 & u32x16::splat(0xffff);
-            let sigma_0_o2_high = sigma_0_o2 >> 16;
-
-            // Compute sigma_0 output
-            let sigma_0_low = sigma_0_o0_low + sigma_0_o1_low + sigma_0_o2_low;
-            let sigma_0_high = sigma_0_o0_high + sigma_0_o1_high + sigma_0_o2_high;
-
-            // SIGMA1
-            // Decomposition over I0
-            let w_2_i0_low = w_2_low & u32x16::splat(Sigma1::I0_L);
-            let w_2_i0_high = w_2_high & u32x16::splat(Sigma1::I0_H);
-            let sigma_1 = small_sigma_1_u32x16(w_2_i0_low + (w_2_i0_high << 16));
-            let sigma_1_o0_low = sigma_1 & u32x16::splat(Sigma1::O0_L);
-            let sigma_1_o0_high = (sigma_1 >> 16) & u32x16::splat(Sigma1::O0_H);
-            let sigma_1_o20 = sigma_1 & u32x16::splat(Sigma1::O2);
-            let sigma_1_o20_pext = pext_u32x16(sigma_1_o20, Sigma1::O2);
-
-            // Decomposition over I1
-            let w_2_i1_low = w_2_low & u32x16::splat(Sigma1::I1_L);
-            let w_2_i1_high = w_2_high & u32x16::splat(Sigma1::I1_H);
-            let sigma_1 = small_sigma_1_u32x16(w_2_i1_low + (w_2_i1_high << 16));
-            let sigma_1_o1_low = sigma_1 & u32x16::splat(Sigma1::O1_L);
-            let sigma_1_o1_high = (sigma_1 >> 16) & u32x16::splat(Sigma1::O1_H);
-            let sigma_1_o21 = sigma_1 & u32x16::splat(Sigma1::O2);
-            let sigma_1_o21_pext = pext_u32x16(sigma_1_o21, Sigma1::O2);
-
-            // XOR the two O2 values
-            let sigma_1_o2 = sigma_1_o20 ^ sigma_1_o21;
-            let sigma_1_o2_low = sigma_1_o2 & u32x16::splat(0xffff);
-            let sigma_1_o2_high = sigma_1_o2 >> 16;
-
-            // Compute sigma_1 output
-            let sigma_1_low = sigma_1_o0_low + sigma_1_o1_low + sigma_1_o2_low;
-            let sigma_1_high = sigma_1_o0_high + sigma_1_o1_high + sigma_1_o2_high;
-
-            // Compute the final output
-            let round_low = w_16_low + sigma_0_low + w_7_low + sigma_1_low;
-            let round_high = w_16_high + sigma_0_high + w_7_high + sigma_1_high;
-            let carry_low = round_low >> 16;
-            let carry_high = (round_high + carry_low) >> 16;
-            let new_w_low = round_low - (carry_low << 16);
-            let new_w_high = round_high + carry_low - (carry_high << 16);
-
-            let trace_values: RoundColumns<u32x16> = RoundColumns {
-                w_15_i0_low: &w_15_i0_low,
-                w_15_i0_high: &w_15_i0_high,
-                sigma_0_o0_low: &sigma_0_o0_low,
-                sigma_0_o0_high: &sigma_0_o0_high,
-                sigma_0_o20_pext: &sigma_0_o20_pext,
-                sigma_0_o1_low: &sigma_0_o1_low,
-                sigma_0_o1_high: &sigma_0_o1_high,
-                sigma_0_o21_pext: &sigma_0_o21_pext,
-                sigma_0_o2_low: &sigma_0_o2_low,
-                sigma_0_o2_high: &sigma_0_o2_high,
-                w_2_i0_low: &w_2_i0_low,
-                w_2_i0_high: &w_2_i0_high,
-                sigma_1_o0_low: &sigma_1_o0_low,
-                sigma_1_o0_high: &sigma_1_o0_high,
-                sigma_1_o20_pext: &sigma_1_o20_pext,
-                sigma_1_o1_low: &sigma_1_o1_low,
-                sigma_1_o1_high: &sigma_1_o1_high,
-                sigma_1_o21_pext: &sigma_1_o21_pext,
-                sigma_1_o2_low: &sigma_1_o2_low,
-                sigma_1_o2_high: &sigma_1_o2_high,
-                carry_low: &carry_low,
-                carry_high: &carry_high,
-            };
-            for (i, value) in trace_values.iter().enumerate() {
-                evals[index + i].push(*value);
-            }
+    // Split the message words into (low, high) limbs
+    let message = pivot::<CHUNK_SIZE>(
+        blocks
+            .array_chunks::<16>()
+            .map(|rows| {
+                std::array::from_fn(|i| {
+                    let word = u32x16::from_array(std::array::from_fn(|lane| rows[lane][i / 2]));
+                    if i % 2 == 0 {
+                        word & u32x16::splat(0xffff)
+                    } else {
+                        word >> 16
+                    }
+                })
+            })
+            .collect(),
+    );
+    for (i, column) in message.into_iter().enumerate() {
+        lookup_data[i] = column.clone();
+        evals[i] = column;
+    }
 
-            let interaction_values: RoundInteractionColumns<u32x16> = RoundInteractionColumns {
-                w_15_i0_low: &w_15_i0_low,
-                w_15_i0_high: &w_15_i0_high,
-                sigma_0_o0_low: &sigma_0_o0_low,
-                sigma_0_o0_high: &sigma_0_o0_high,
-                sigma_0_o20_pext: &sigma_0_o20_pext,
-                w_15_i1_low: &w_15_i1_low,
-                w_15_i1_high: &w_15_i1_high,
-                sigma_0_o1_low: &sigma_0_o1_low,
-                sigma_0_o1_high: &sigma_0_o1_high,
-                sigma_0_o21_pext: &sigma_0_o21_pext,
-                sigma_0_o2_low: &sigma_0_o2_low,
-                sigma_0_o2_high: &sigma_0_o2_high,
-                w_2_i0_low: &w_2_i0_low,
-                w_2_i0_high: &w_2_i0_high,
-                sigma_1_o0_low: &sigma_1_o0_low,
-                sigma_1_o0_high: &sigma_1_o0_high,
-                sigma_1_o20_pext: &sigma_1_o20_pext,
-                w_2_i1_low: &w_2_i1_low,
-                w_2_i1_high: &w_2_i1_high,
-                sigma_1_o1_low: &sigma_1_o1_low,
-                sigma_1_o1_high: &sigma_1_o1_high,
-                sigma_1_o21_pext: &sigma_1_o21_pext,
-                sigma_1_o2_low: &sigma_1_o2_low,
-                sigma_1_o2_high: &sigma_1_o2_high,
-                new_w_low: &new_w_low,
-                new_w_high: &new_w_high,
-                carry_low: &carry_low,
-                carry_high: &carry_high,
-            };
-            for (i, value) in interaction_values.iter().enumerate() {
-                lookup_data[interaction_index + i].push(*value);
+    // Each chunk of rows is filled by its own thread
+    let chunk_size = par_chunk_size(simd_size);
+    row_chunks_mut(&mut evals, chunk_size)
+        .into_par_iter()
+        .zip(row_chunks_mut(&mut lookup_data, chunk_size))
+        .for_each(|(mut evals, mut lookup_data)| {
+            for t in 16..(16 + N_SCHEDULING_ROUNDS) {
+                let index = W_SIZE + (t - 16) * RoundColumns::SIZE;
+                let interaction_index = W_SIZE + (t - 16) * RoundInteractionColumns::SIZE;
+
+                for simd_row in 0..evals[0].len() {
+                    // Load the W values
+                    let w_16_low = evals[2 * (t - 16)][simd_row];
+                    let w_16_high = evals[2 * (t - 16) + 1][simd_row];
+                    let w_15_low = evals[2 * (t - 15)][simd_row];
+                    let w_15_high = evals[2 * (t - 15) + 1][simd_row];
+                    let w_7_low = evals[2 * (t - 7)][simd_row];
+                    let w_7_high = evals[2 * (t - 7) + 1][simd_row];
+                    let w_2_low = evals[2 * (t - 2)][simd_row];
+                    let w_2_high = evals[2 * (t - 2) + 1][simd_row];
+
+                    // SIGMA0
+                    // Decomposition over I0
+                    let w_15_i0_low = w_15_low & u32x16::splat(Sigma0::I0_L);
+                    let w_15_i0_high = w_15_high & u32x16::splat(Sigma0::I0_H);
+                    let sigma_0 = small_sigma_0_u32x16(w_15_i0_low + (w_15_i0_high << 16));
+                    let sigma_0_o0_low = sigma_0 & u32x16::splat(Sigma0::O0_L);
+                    let sigma_0_o0_high = (sigma_0 >> 16) & u32x16::splat(Sigma0::O0_H);
+                    let sigma_0_o20 = sigma_0 & u32x16::splat(Sigma0::O2);
+                    let sigma_0_o20_pext = pext_u32x16(sigma_0_o20, Sigma0::O2);
+
+                    // Decomposition over I1
+                    let w_15_i1_low = w_15_low & u32x16::splat(Sigma0::I1_L);
+                    let w_15_i1_high = w_15_high & u32x16::splat(Sigma0::I1_H);
+                    let sigma_0 = small_sigma_0_u32x16(w_15_i1_low + (w_15_i1_high << 16));
+                    let sigma_0_o1_low = sigma_0 & u32x16::splat(Sigma0::O1_L);
+                    let sigma_0_o1_high = (sigma_0 >> 16) & u32x16::splat(Sigma0::O1_H);
+                    let sigma_0_o21 = sigma_0 & u32x16::splat(Sigma0::O2);
+                    let sigma_0_o21_pext = pext_u32x16(sigma_0_o21, Sigma0::O2);
+
+                    // XOR the two O2 values
+                    let sigma_0_o2 = sigma_0_o20 ^ sigma_0_o21;
+                    let sigma_0_o2_low = sigma_0_o2 & u32x16::splat(0xffff);
+                    let sigma_0_o2_high = sigma_0_o2 >> 16;
+
+                    // Compute sigma_0 output
+                    let sigma_0_low = sigma_0_o0_low + sigma_0_o1_low + sigma_0_o2_low;
+                    let sigma_0_high = sigma_0_o0_high + sigma_0_o1_high + sigma_0_o2_high;
+
+                    // SIGMA1
+                    // Decomposition over I0
+                    let w_2_i0_low = w_2_low & u32x16::splat(Sigma1::I0_L);
+                    let w_2_i0_high = w_2_high & u32x16::splat(Sigma1::I0_H);
+                    let sigma_1 = small_sigma_1_u32x16(w_2_i0_low + (w_2_i0_high << 16));
+                    let sigma_1_o0_low = sigma_1 & u32x16::splat(Sigma1::O0_L);
+                    let sigma_1_o0_high = (sigma_1 >> 16) & u32x16::splat(Sigma1::O0_H);
+                    let sigma_1_o20 = sigma_1 & u32x16::splat(Sigma1::O2);
+                    let sigma_1_o20_pext = pext_u32x16(sigma_1_o20, Sigma1::O2);
+
+                    // Decomposition over I1
+                    let w_2_i1_low = w_2_low & u32x16::splat(Sigma1::I1_L);
+                    let w_2_i1_high = w_2_high & u32x16::splat(Sigma1::I1_H);
+                    let sigma_1 = small_sigma_1_u32x16(w_2_i1_low + (w_2_i1_high << 16));
+                    let sigma_1_o1_low = sigma_1 & u32x16::splat(Sigma1::O1_L);
+                    let sigma_1_o1_high = (sigma_1 >> 16) & u32x16::splat(Sigma1::O1_H);
+                    let sigma_1_o21 = sigma_1 & u32x16::splat(Sigma1::O2);
+                    let sigma_1_o21_pext = pext_u32x16(sigma_1_o21, Sigma1::O2);
+
+                    // XOR the two O2 values
+                    let sigma_1_o2 = sigma_1_o20 ^ sigma_1_o21;
+                    let sigma_1_o2_low = sigma_1_o2 & u32x16::splat(0xffff);
+                    let sigma_1_o2_high = sigma_1_o2 >> 16;
+
+                    // Compute sigma_1 output
+                    let sigma_1_low = sigma_1_o0_low + sigma_1_o1_low + sigma_1_o2_low;
+                    let sigma_1_high = sigma_1_o0_high + sigma_1_o1_high + sigma_1_o2_high;
+
+                    // Compute the final output
+                    let round_low = w_16_low + sigma_0_low + w_7_low + sigma_1_low;
+                    let round_high = w_16_high + sigma_0_high + w_7_high + sigma_1_high;
+                    let carry_low = round_low >> 16;
+                    let carry_high = (round_high + carry_low) >> 16;
+                    let new_w_low = round_low - (carry_low << 16);
+                    let new_w_high = round_high + carry_low - (carry_high << 16);
+
+                    let trace_values: RoundColumns<u32x16> = RoundColumns {
+                        w_15_i0_low: &w_15_i0_low,
+                        w_15_i0_high: &w_15_i0_high,
+                        sigma_0_o0_low: &sigma_0_o0_low,
+                        sigma_0_o0_high: &sigma_0_o0_high,
+                        sigma_0_o20_pext: &sigma_0_o20_pext,
+                        sigma_0_o1_low: &sigma_0_o1_low,
+                        sigma_0_o1_high: &sigma_0_o1_high,
+                        sigma_0_o21_pext: &sigma_0_o21_pext,
+                        sigma_0_o2_low: &sigma_0_o2_low,
+                        sigma_0_o2_high: &sigma_0_o2_high,
+                        w_2_i0_low: &w_2_i0_low,
+                        w_2_i0_high: &w_2_i0_high,
+                        sigma_1_o0_low: &sigma_1_o0_low,
+                        sigma_1_o0_high: &sigma_1_o0_high,
+                        sigma_1_o20_pext: &sigma_1_o20_pext,
+                        sigma_1_o1_low: &sigma_1_o1_low,
+                        sigma_1_o1_high: &sigma_1_o1_high,
+                        sigma_1_o21_pext: &sigma_1_o21_pext,
+                        sigma_1_o2_low: &sigma_1_o2_low,
+                        sigma_1_o2_high: &sigma_1_o2_high,
+                        carry_low: &carry_low,
+                        carry_high: &carry_high,
+                    };
+                    for (i, value) in trace_values.iter().enumerate() {
+                        evals[index + i][simd_row] = *value;
+                    }
+
+                    let interaction_values: RoundInteractionColumns<u32x16> =
+                        RoundInteractionColumns {
+                            w_15_i0_low: &w_15_i0_low,
+                            w_15_i0_high: &w_15_i0_high,
+                            sigma_0_o0_low: &sigma_0_o0_low,
+                            sigma_0_o0_high: &sigma_0_o0_high,
+                            sigma_0_o20_pext: &sigma_0_o20_pext,
+                            w_15_i1_low: &w_15_i1_low,
+                            w_15_i1_high: &w_15_i1_high,
+                            sigma_0_o1_low: &sigma_0_o1_low,
+                            sigma_0_o1_high: &sigma_0_o1_high,
+                            sigma_0_o21_pext: &sigma_0_o21_pext,
+                            sigma_0_o2_low: &sigma_0_o2_low,
+                            sigma_0_o2_high: &sigma_0_o2_high,
+                            w_2_i0_low: &w_2_i0_low,
+                            w_2_i0_high: &w_2_i0_high,
+                            sigma_1_o0_low: &sigma_1_o0_low,
+                            sigma_1_o0_high: &sigma_1_o0_high,
+                            sigma_1_o20_pext: &sigma_1_o20_pext,
+                            w_2_i1_low: &w_2_i1_low,
+                            w_2_i1_high: &w_2_i1_high,
+                            sigma_1_o1_low: &sigma_1_o1_low,
+                            sigma_1_o1_high: &sigma_1_o1_high,
+                            sigma_1_o21_pext: &sigma_1_o21_pext,
+                            sigma_1_o2_low: &sigma_1_o2_low,
+                            sigma_1_o2_high: &sigma_1_o2_high,
+                            new_w_low: &new_w_low,
+                            new_w_high: &new_w_high,
+                            carry_low: &carry_low,
+                            carry_high: &carry_high,
+                        };
+                    for (i, value) in interaction_values.iter().enumerate() {
+                        lookup_data[interaction_index + i][simd_row] = *value;
+                    }
+
+                    evals[2 * t][simd_row] = new_w_low;
+                    evals[2 * t + 1][simd_row] = new_w_high;
+                    lookup_data[2 * t][simd_row] = new_w_low;
+                    lookup_data[2 * t + 1][simd_row] = new_w_high;
+                }
             }
-
-            evals[2 * t].push(new_w_low);
-            evals[2 * t + 1].push(new_w_high);
-            lookup_data[2 * t].push(new_w_low);
-            lookup_data[2 * t + 1].push(new_w_high);
-        }
-    }
+        });
 
     let domain = CanonicCoset::new(log_size).circle_domain();
     let trace = evals
@@ -367,6 +412,19 @@ mod tests {
         assert_eq!(trace.len(), N_COLUMNS);
     }
 
+    #[test]
+    fn test_gen_trace_from_blocks_message() {
+        let blocks = gen_blocks(LOG_N_LANES + 1);
+        let (trace, _) = gen_trace_from_blocks(&blocks);
+        for (row, block) in blocks.iter().enumerate() {
+            for (j, word) in block.iter().enumerate() {
+                let low = trace[2 * j].values.at(row).0;
+                let high = trace[2 * j + 1].values.at(row).0;
+                assert_eq!(low + (high << 16), *word);
+            }
+        }
+    }
+
     #[test]
     fn test_gen_trace_values() {
         let log_size = LOG_N_LANES;
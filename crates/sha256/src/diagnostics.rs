@@ -0,0 +1,178 @@
+//! Diagnostics of unbalanced relations.
+//!
+//! Every value added to a relation, by a component or by the verifier, is tracked along with its
+//! multiplicity. The values whose multiplicities do not sum to zero are reported with each of
+//! their uses.
+
+use std::{collections::HashMap, fmt};
+
+use itertools::Itertools;
+use serde::Serialize;
+use stwo::{
+    core::{
+        fields::m31::{M31, P},
+        utils::{bit_reverse_index, coset_index_to_circle_domain_index},
+    },
+    prover::backend::simd::m31::N_LANES,
+};
+use stwo_constraint_framework::relation_tracker::RelationTrackerEntry;
+
+/// Number of uses printed for each unbalanced value.
+const MAX_DISPLAYED_USES: usize = 8;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RelationUse {
+    /// Component emitting (positive multiplicity) or consuming (negative multiplicity) the value,
+    /// `verifier` for the public data.
+    pub component: String,
+    /// Row of the component trace, in the order the witness is generated.
+    pub row: usize,
+    pub multiplicity: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Imbalance {
+    /// Path of the relation in [`crate::relations::Relations`], e.g. `maj::i1_low_0`.
+    pub relation: String,
+    pub values: Vec<u32>,
+    /// Sum of the multiplicities of all the uses.
+    pub multiplicity: i64,
+    pub uses: Vec<RelationUse>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Diagnostics {
+    pub imbalances: Vec<Imbalance>,
+}
+
+/// Relation tracker entries of a component, in the order of the relation tracker.
+pub struct ComponentEntries {
+    pub component: String,
+    pub log_size: u32,
+    pub entries: Vec<RelationTrackerEntry>,
+}
+
+impl ComponentEntries {
+    /// Rows of the entries.
+    ///
+    /// The relation tracker goes through the trace in circle domain order, `N_LANES` rows at a
+    /// time, and unpacks every relation use of these rows one after the other.
+    fn rows(&self) -> impl Iterator<Item = usize> + '_ {
+        let n_uses = self.entries.len() >> self.log_size;
+        (0..self.entries.len()).map(move |i| {
+            let index = (i / (n_uses * N_LANES)) * N_LANES + i % N_LANES;
+            bit_reverse_index(
+                coset_index_to_circle_domain_index(index, self.log_size),
+                self.log_size,
+            )
+        })
+    }
+}
+
+impl Diagnostics {
+    pub fn new(
+        components: Vec<ComponentEntries>,
+        verifier: Vec<(usize, RelationTrackerEntry)>,
+        names: &HashMap<String, String>,
+    ) -> Self {
+        let mut uses: HashMap<(String, Vec<u32>), Vec<RelationUse>> = HashMap::new();
+        let mut add_use = |component: &str, row: usize, entry: RelationTrackerEntry| {
+            if entry.mult == M31::from(0) {
+                return;
+            }
+            let relation = names
+                .get(&entry.relation)
+                .cloned()
+                .unwrap_or(entry.relation);
+            let values = entry.values.iter().map(|value| value.0).collect();
+            uses.entry((relation, values))
+                .or_default()
+                .push(RelationUse {
+                    component: component.to_string(),
+                    row,
+                    multiplicity: to_signed(entry.mult),
+                });
+        };
+
+        for component in &components {
+            for (row, entry) in component.rows().zip(component.entries.iter().cloned()) {
+                add_use(&component.component, row, entry);
+            }
+        }
+        for (row, entry) in verifier {
+            add_use("verifier", row, entry);
+        }
+
+        let mut imbalances: Vec<Imbalance> = uses
+            .into_iter()
+            .filter_map(|((relation, values), uses)| {
+                let multiplicity = uses.iter().map(|u| u.multiplicity).sum::<i64>();
+                (multiplicity.rem_euclid(P as i64) != 0).then_some(Imbalance {
+                    relation,
+                    values,
+                    multiplicity,
+                    uses,
+                })
+            })
+            .collect();
+        imbalances.sort_by(|a, b| (&a.relation, &a.values).cmp(&(&b.relation, &b.values)));
+
+        Self { imbalances }
+    }
+
+    /// Components using an unbalanced value, sorted.
+    pub fn components(&self) -> Vec<String> {
+        self.imbalances
+            .iter()
+            .flat_map(|imbalance| &imbalance.uses)
+            .map(|relation_use| relation_use.component.clone())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.imbalances.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} unbalanced relation values", self.imbalances.len())?;
+        for imbalance in &self.imbalances {
+            writeln!(
+                f,
+                "{} {:?}: multiplicity {}",
+                imbalance.relation, imbalance.values, imbalance.multiplicity
+            )?;
+            for relation_use in imbalance.uses.iter().take(MAX_DISPLAYED_USES) {
+                writeln!(
+                    f,
+                    "    {:+} by {} at row {}",
+                    relation_use.multiplicity, relation_use.component, relation_use.row
+                )?;
+            }
+            if imbalance.uses.len() > MAX_DISPLAYED_USES {
+                writeln!(
+                    f,
+                    "    and {} more",
+                    imbalance.uses.len() - MAX_DISPLAYED_USES
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Multiplicities are small, read the upper half of the field as negative values.
+fn to_signed(value: M31) -> i64 {
+    if value.0 > P / 2 {
+        value.0 as i64 - P as i64
+    } else {
+        value.0 as i64
+    }
+}
@@ -0,0 +1,224 @@
+//! Serializable SHA-256 proof, with everything the verifier needs next to the proof itself.
+
+use serde::{Deserialize, Serialize};
+use stwo::core::{
+    pcs::PcsConfig,
+    proof::StarkProof,
+    vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+    verifier::VerificationError,
+};
+use thiserror::Error;
+
+use crate::{components::ClaimedSum, public::PublicData, verify_sha256, Sha256Proof};
+
+/// Version of the envelope layout, bumped on any breaking change.
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum EnvelopeError {
+    #[error("Unsupported envelope version {found}, expected {}", FORMAT_VERSION)]
+    UnsupportedVersion { found: u32 },
+    #[error("Bincode error: {0}")]
+    Bincode(#[from] bincode::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Envelope of a proof committed with Blake2s.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sha256ProofEnvelope {
+    /// Must stay the first field, it is read before the rest of the envelope.
+    pub version: u32,
+    pub log_size: u32,
+    pub config: PcsConfig,
+    pub claimed_sum: ClaimedSum,
+    pub public_data: PublicData,
+    pub proof: StarkProof<Blake2sMerkleHasher>,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+impl Sha256ProofEnvelope {
+    pub fn new(
+        log_size: u32,
+        config: PcsConfig,
+        public_data: PublicData,
+        proof: Sha256Proof<Blake2sMerkleHasher>,
+    ) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            log_size,
+            config,
+            claimed_sum: proof.claimed_sum,
+            public_data,
+            proof: proof.stark_proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EnvelopeError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        check_version(bincode::deserialize::<u32>(bytes)?)?;
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, EnvelopeError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, EnvelopeError> {
+        check_version(serde_json::from_str::<Version>(json)?.version)?;
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Verify the proof with the PCS config `config` expected by the caller. The config of the
+    /// envelope is chosen by the sender, so an envelope with any other config is rejected rather
+    /// than verified at a security level the caller did not ask for.
+    pub fn verify(self, config: PcsConfig) -> Result<(), VerificationError> {
+        if self.config != config {
+            return Err(VerificationError::InvalidStructure(format!(
+                "Envelope config {:?} differs from the expected {config:?}",
+                self.config
+            )));
+        }
+        verify_sha256::<Blake2sMerkleChannel>(
+            self.log_size,
+            config,
+            &self.public_data,
+            Sha256Proof {
+                claimed_sum: self.claimed_sum,
+                stark_proof: self.proof,
+            },
+        )
+    }
+}
+
+fn check_version(found: u32) -> Result<(), EnvelopeError> {
+    if found != FORMAT_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion { found });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo::prover::backend::simd::m31::LOG_N_LANES;
+
+    use super::*;
+    use crate::{components::scheduling::witness::gen_blocks, prove_sha256_blocks, ProverOptions};
+
+    #[test_log::test]
+    fn test_envelope_round_trip() {
+        const LOG_SIZE: u32 = 13;
+        let config = PcsConfig::default();
+        let blocks = gen_blocks(LOG_SIZE);
+        let (proof, digests) =
+            prove_sha256_blocks::<Blake2sMerkleChannel>(&blocks, ProverOptions::default(), config)
+                .unwrap();
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: None,
+        };
+        let envelope = Sha256ProofEnvelope::new(LOG_SIZE, config, public_data, proof);
+
+        let bytes = envelope.to_bytes().unwrap();
+        let decoded = Sha256ProofEnvelope::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        decoded.verify(config).unwrap();
+
+        let json = envelope.to_json().unwrap();
+        let decoded = Sha256ProofEnvelope::from_json(&json).unwrap();
+        assert_eq!(decoded.to_json().unwrap(), json);
+        decoded.verify(config).unwrap();
+
+        let mut unsupported = envelope;
+        unsupported.version = FORMAT_VERSION + 1;
+        assert!(matches!(
+            Sha256ProofEnvelope::from_bytes(&unsupported.to_bytes().unwrap()),
+            Err(EnvelopeError::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            Sha256ProofEnvelope::from_json(&unsupported.to_json().unwrap()),
+            Err(EnvelopeError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_envelope_rejects_oversized_statement() {
+        let config = PcsConfig::default();
+        let (proof, digests) = prove_sha256_blocks::<Blake2sMerkleChannel>(
+            &gen_blocks(LOG_N_LANES),
+            ProverOptions::default(),
+            config,
+        )
+        .unwrap();
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: None,
+        };
+        let envelope = Sha256ProofEnvelope::new(LOG_N_LANES, config, public_data, proof);
+        let verify = |envelope: &Sha256ProofEnvelope| {
+            Sha256ProofEnvelope::from_bytes(&envelope.to_bytes().unwrap())
+                .unwrap()
+                .verify(envelope.config)
+        };
+
+        // A single message of 2^31 blocks, matching its log size.
+        let mut oversized = envelope.clone();
+        oversized.log_size = 31;
+        oversized.public_data.digests.truncate(1);
+        oversized.public_data.n_blocks = vec![1 << 31];
+        assert!(matches!(
+            verify(&oversized),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+
+        // The same message in a trace of a valid log size.
+        oversized.log_size = LOG_N_LANES;
+        assert!(matches!(
+            verify(&oversized),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+
+        // A blowup factor leaving no room for the trace.
+        let mut oversized = envelope;
+        oversized.config.fri_config.log_blowup_factor = u32::MAX;
+        assert!(matches!(
+            verify(&oversized),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_envelope_rejects_weakened_config() {
+        let config = PcsConfig::default();
+        let (proof, digests) = prove_sha256_blocks::<Blake2sMerkleChannel>(
+            &gen_blocks(LOG_N_LANES),
+            ProverOptions::default(),
+            config,
+        )
+        .unwrap();
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: None,
+        };
+        let envelope = Sha256ProofEnvelope::new(LOG_N_LANES, config, public_data, proof);
+        envelope.clone().verify(config).unwrap();
+
+        let mut weakened = envelope;
+        weakened.config.pow_bits = 0;
+        weakened.config.fri_config.n_queries = 0;
+        assert!(matches!(
+            weakened.verify(config),
+            Err(VerificationError::InvalidStructure(_))
+        ));
+    }
+}
@@ -10,11 +10,17 @@
 mod smalloc_init;
 
 pub mod components;
+pub mod diagnostics;
+pub mod envelope;
 pub mod macros;
+pub mod mask_search;
+pub mod messages;
 pub mod partitions;
 pub mod preprocessed;
+pub mod public;
 pub mod relations;
 pub mod sha256;
+pub mod stats;
 
 #[cfg(feature = "peak-alloc")]
 use peak_alloc::PeakAlloc;
@@ -47,139 +53,612 @@ use rpmalloc::RpMalloc;
 #[global_allocator]
 static ALLOC: RpMalloc = RpMalloc;
 
+#[cfg(feature = "span-alloc")]
+use utils::alloc::SpanAlloc;
+#[cfg(feature = "span-alloc")]
+#[global_allocator]
+static ALLOC: SpanAlloc = SpanAlloc::new();
+
 use num_traits::Zero;
 use stwo::{
     core::{
-        channel::Blake2sChannel,
-        fields::qm31::SecureField,
-        pcs::PcsConfig,
+        channel::MerkleChannel,
+        circle::M31_CIRCLE_LOG_ORDER,
+        fields::{m31::BaseField, qm31::SecureField},
+        pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec},
         poly::circle::CanonicCoset,
         proof::StarkProof,
-        vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+        vcs::MerkleHasher,
+        verifier::{verify, VerificationError},
+        ColumnVec,
     },
-    prover::{backend::simd::SimdBackend, poly::circle::PolyOps, prove, CommitmentSchemeProver},
+    prover::{
+        backend::{
+            simd::{m31::LOG_N_LANES, SimdBackend},
+            BackendForChannel,
+        },
+        poly::{
+            circle::{CircleEvaluation, PolyOps},
+            twiddles::TwiddleTree,
+            BitReversedOrder,
+        },
+        prove,
+        vcs::prover::MerkleProver,
+        CommitmentSchemeProver, CommitmentTreeProver, ProvingError,
+    },
+};
+use stwo_constraint_framework::{
+    preprocessed_columns::PreProcessedColumnId, TraceLocationAllocator,
 };
-use stwo_constraint_framework::TraceLocationAllocator;
-use tracing::{debug, info, span, Level};
+use thiserror::Error;
+use tracing::{debug, error, info, span, Level};
 
 use crate::{
-    components::{gen_interaction_trace, gen_trace},
+    components::{gen_interaction_trace, gen_trace, scheduling::witness::gen_blocks, ClaimedSum},
+    diagnostics::Diagnostics,
+    messages::{padded_len, Rows},
     preprocessed::PreProcessedTrace,
+    public::PublicData,
     relations::Relations,
 };
 
-pub fn prove_sha256(log_size: u32, config: PcsConfig) -> StarkProof<Blake2sMerkleHasher> {
-    // Precompute twiddles.
-    let span = span!(Level::INFO, "Precompute twiddles").entered();
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + 2)
-            .circle_domain()
-            .half_coset,
-    );
-    span.exit();
+#[derive(Clone, Debug)]
+pub struct Sha256Proof<H: MerkleHasher> {
+    pub claimed_sum: ClaimedSum,
+    pub stark_proof: StarkProof<H>,
+}
+
+/// Options of the SHA-256 provers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProverOptions {
+    /// Make the message blocks part of the public statement.
+    pub public_blocks: bool,
+    /// Track the values added to each relation when the logup sum does not balance, and report
+    /// the unbalanced ones in [`Sha256ProverError::UnbalancedRelation`]. Slow, for debugging.
+    pub track_relations: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum Sha256ProverError {
+    /// The logup sums of the components and the public data do not cancel out.
+    ///
+    /// The components at fault are only known with [`ProverOptions::track_relations`], as a
+    /// relation is shared by several components and only the total of their sums is checked.
+    #[error(
+        "Relation summary is not zero: {sum}, components: {components:?}, claimed sums: \
+         {claimed_sum:?}"
+    )]
+    UnbalancedRelation {
+        sum: SecureField,
+        claimed_sum: Box<ClaimedSum>,
+        /// Components, and `verifier` for the public data, using an unbalanced relation value.
+        /// Empty unless [`ProverOptions::track_relations`] is set.
+        components: Vec<String>,
+        /// Unbalanced relation values, if [`ProverOptions::track_relations`] is set.
+        diagnostics: Option<Box<Diagnostics>>,
+    },
+    #[error("Invalid log size {log_size}, expected between {min} and {max}")]
+    InvalidLogSize { log_size: u32, min: u32, max: u32 },
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Proving error: {0}")]
+    Proving(#[from] ProvingError),
+}
+
+/// Largest trace log size, such that the twiddles still fit in the M31 circle.
+pub fn max_log_size(config: PcsConfig) -> u32 {
+    M31_CIRCLE_LOG_ORDER
+        .saturating_sub(config.fri_config.log_blowup_factor)
+        .saturating_sub(2)
+}
+
+fn check_log_size(log_size: u32, config: PcsConfig) -> Result<(), Sha256ProverError> {
+    let max = max_log_size(config);
+    if !(LOG_N_LANES..=max).contains(&log_size) {
+        return Err(Sha256ProverError::InvalidLogSize {
+            log_size,
+            min: LOG_N_LANES,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// Log size of the smallest trace holding `n_rows` real rows.
+fn trace_log_size(n_rows: usize) -> Result<u32, Sha256ProverError> {
+    padded_len(n_rows).map(usize::ilog2).ok_or_else(|| {
+        Sha256ProverError::InvalidInput(format!("{n_rows} blocks overflow the trace"))
+    })
+}
+
+/// Prove SHA-256 over `2^log_size` synthetic message blocks.
+pub fn prove_sha256<MC: MerkleChannel>(
+    log_size: u32,
+    config: PcsConfig,
+) -> Result<Sha256Proof<MC::H>, Sha256ProverError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    Sha256Prover::<MC>::new(log_size, config)?.prove()
+}
+
+/// Prove SHA-256 over caller-supplied message blocks, one block per row.
+///
+/// See [`Sha256Prover::prove_blocks`], the trace is as small as the number of blocks allows.
+pub fn prove_sha256_blocks<MC: MerkleChannel>(
+    blocks: &[[u32; 16]],
+    options: ProverOptions,
+    config: PcsConfig,
+) -> Result<(Sha256Proof<MC::H>, Vec<[u32; 8]>), Sha256ProverError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    Sha256Prover::<MC>::new(trace_log_size(blocks.len())?, config)?.prove_blocks(blocks, options)
+}
+
+/// Prove SHA-256 over caller-supplied padded messages, one block per row.
+///
+/// See [`Sha256Prover::prove_messages`], the trace is as small as the number of blocks allows.
+pub fn prove_sha256_messages<MC: MerkleChannel>(
+    messages: &[Vec<[u32; 16]>],
+    options: ProverOptions,
+    config: PcsConfig,
+) -> Result<(Sha256Proof<MC::H>, PublicData), Sha256ProverError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    check_messages(messages)?;
+    let n_rows = messages.iter().map(Vec::len).sum::<usize>();
+    Sha256Prover::<MC>::new(trace_log_size(n_rows)?, config)?.prove_messages(messages, options)
+}
+
+fn check_messages(messages: &[Vec<[u32; 16]>]) -> Result<(), Sha256ProverError> {
+    if let Some(msg_id) = messages.iter().position(Vec::is_empty) {
+        return Err(Sha256ProverError::InvalidInput(format!(
+            "Message {msg_id} has no block"
+        )));
+    }
+    Ok(())
+}
+
+/// Prover session for a trace size, PCS config and Merkle channel.
+///
+/// The twiddles and the commitment to the preprocessed trace are computed once, and reused by
+/// every proof of the session. Only the computation is shared: the stwo commitment scheme owns its
+/// trees, so each proof copies the preprocessed polynomials, evaluations and Merkle layers, and
+/// holds them in memory until it completes. The session is `Sync`, so that proofs can run from
+/// several threads.
+pub struct Sha256Prover<MC: MerkleChannel>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    log_size: u32,
+    config: PcsConfig,
+    twiddles: TwiddleTree<SimdBackend>,
+    preprocessed_ids: Vec<PreProcessedColumnId>,
+    /// Interpolated, extended and committed preprocessed trace.
+    preprocessed_tree: CommitmentTreeProver<SimdBackend, MC>,
+}
+
+impl<MC: MerkleChannel> Sha256Prover<MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    pub fn new(log_size: u32, config: PcsConfig) -> Result<Self, Sha256ProverError> {
+        check_log_size(log_size, config)?;
+
+        // Precompute twiddles.
+        let span = span!(Level::INFO, "Precompute twiddles").entered();
+        let twiddles = SimdBackend::precompute_twiddles(
+            CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + 2)
+                .circle_domain()
+                .half_coset,
+        );
+        span.exit();
+
+        // Preprocessed trace.
+        let span = span!(Level::INFO, "Constant").entered();
+        let span_1 = span!(Level::INFO, "Simd generation").entered();
+        let preprocessed_trace = PreProcessedTrace::new(log_size);
+        span_1.exit();
+        let span_2 = span!(Level::INFO, "Commit").entered();
+        let preprocessed_tree =
+            commit_preprocessed::<MC>(config, &twiddles, preprocessed_trace.trace);
+        span_2.exit();
+        span.exit();
+
+        Ok(Self {
+            log_size,
+            config,
+            twiddles,
+            preprocessed_ids: preprocessed_trace.ids,
+            preprocessed_tree,
+        })
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn config(&self) -> PcsConfig {
+        self.config
+    }
+
+    /// Prove SHA-256 over `2^log_size` synthetic message blocks.
+    pub fn prove(&self) -> Result<Sha256Proof<MC::H>, Sha256ProverError> {
+        let (proof, _) = self.prove_blocks(&gen_blocks(self.log_size), ProverOptions::default())?;
+        Ok(proof)
+    }
+
+    /// Prove SHA-256 over caller-supplied message blocks, one block per row.
+    ///
+    /// Each block is compressed once from the initial hash value `H`. The trace is filled with
+    /// padding rows after them up to the session log size, which the number of blocks must fit.
+    /// Returns the proof along with the digest of each block.
+    ///
+    /// The digests are public outputs of the proof, to be supplied by the verifier. If
+    /// [`ProverOptions::public_blocks`] is set, so are the message blocks.
+    pub fn prove_blocks(
+        &self,
+        blocks: &[[u32; 16]],
+        options: ProverOptions,
+    ) -> Result<(Sha256Proof<MC::H>, Vec<[u32; 8]>), Sha256ProverError> {
+        let (proof, public_data) = self.prove_rows(Rows::from_blocks(blocks), options)?;
+        Ok((proof, public_data.digests))
+    }
+
+    /// Prove SHA-256 over caller-supplied padded messages, one block per row.
+    ///
+    /// Each message is compressed block after block from the initial hash value `H`, see
+    /// [`messages::pad_message`]. The trace is filled with padding rows after them up to the
+    /// session log size, which the total number of blocks must fit. Returns the proof along with
+    /// the public data to verify it against.
+    pub fn prove_messages(
+        &self,
+        messages: &[Vec<[u32; 16]>],
+        options: ProverOptions,
+    ) -> Result<(Sha256Proof<MC::H>, PublicData), Sha256ProverError> {
+        check_messages(messages)?;
+        self.prove_rows(Rows::new(messages), options)
+    }
+
+    fn prove_rows(
+        &self,
+        mut rows: Rows,
+        options: ProverOptions,
+    ) -> Result<(Sha256Proof<MC::H>, PublicData), Sha256ProverError> {
+        let log_size = self.log_size;
+        let config = self.config;
+        if rows.len() > 1 << log_size {
+            return Err(Sha256ProverError::InvalidInput(format!(
+                "{} blocks do not fit a trace of log size {log_size}",
+                rows.len()
+            )));
+        }
+
+        // Public data, without the padding rows.
+        let public_data = PublicData {
+            digests: rows.digests.clone(),
+            n_blocks: rows.n_blocks(),
+            blocks: options.public_blocks.then(|| rows.blocks.clone()),
+        };
+        rows.pad(log_size);
+
+        // Setup protocol.
+        let channel = &mut MC::C::default();
+        config.mix_into(channel);
+        let mut commitment_scheme = CommitmentSchemeProver::<_, MC>::new(config, &self.twiddles);
+
+        // Preprocessed trace, committed once by the session and copied into the commitment scheme
+        // of this proof.
+        let span = span!(Level::INFO, "Constant").entered();
+        let tree = &self.preprocessed_tree;
+        MC::mix_root(channel, tree.commitment.root());
+        commitment_scheme.trees.push(CommitmentTreeProver {
+            polynomials: tree.polynomials.clone(),
+            evaluations: tree.evaluations.clone(),
+            commitment: MerkleProver {
+                layers: tree.commitment.layers.clone(),
+            },
+        });
+        span.exit();
+
+        // Trace.
+        let span = span!(Level::INFO, "Trace").entered();
+        let (trace, lookup_data) = gen_trace(&rows);
+        let span_1 = span!(Level::INFO, "Extend evals").entered();
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(trace);
+        tree_builder.commit(channel);
+        span_1.exit();
+        span.exit();
+
+        // Public data.
+        public_data.mix_into(channel);
+
+        // Draw lookup elements.
+        let relations = Relations::draw(channel);
+
+        // Interaction trace.
+        let span = span!(Level::INFO, "Interaction").entered();
+        let (trace, claimed_sum) =
+            gen_interaction_trace(lookup_data, &relations, options.public_blocks);
+        claimed_sum.mix_into(channel);
+        let span_1 = span!(Level::INFO, "Extend evals").entered();
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(trace);
+        tree_builder.commit(channel);
+        span_1.exit();
+        span.exit();
+
+        debug!(
+            "Columns count: {:?}",
+            commitment_scheme
+                .trees
+                .as_ref()
+                .map(|tree| tree.polynomials.len())
+        );
+        debug!(
+            "Columns length: {:?}",
+            commitment_scheme.trees.as_ref().map(|tree| {
+                let max_len = tree
+                    .polynomials
+                    .iter()
+                    .map(|poly| poly.evals.values.len().ilog2())
+                    .collect::<Vec<_>>()
+                    .iter()
+                    .copied()
+                    .max()
+                    .unwrap();
+                assert!(max_len <= log_size + 1);
+                max_len
+            })
+        );
+
+        // Prove constraints.
+        let span = span!(Level::INFO, "Prove").entered();
+        let trace_allocator =
+            &mut TraceLocationAllocator::new_with_preprocessed_columns(&self.preprocessed_ids);
+        let components = components::Components::new(
+            log_size,
+            trace_allocator,
+            &relations,
+            &claimed_sum,
+            options.public_blocks,
+        );
+        debug!(
+            "Trace log degree bounds: {:?}",
+            components.trace_log_degree_bounds()
+        );
+
+        let total_sum = claimed_sum.sum() + public_data.logup_sum(&relations, log_size);
+        if total_sum != SecureField::zero() {
+            let diagnostics = options.track_relations.then(|| {
+                let diagnostics =
+                    components.track_relations(&commitment_scheme, &relations, &public_data);
+                error!("{diagnostics}");
+                Box::new(diagnostics)
+            });
+            return Err(Sha256ProverError::UnbalancedRelation {
+                sum: total_sum,
+                claimed_sum: Box::new(claimed_sum),
+                components: diagnostics
+                    .as_ref()
+                    .map_or_else(Vec::new, |diagnostics| diagnostics.components()),
+                diagnostics,
+            });
+        }
+
+        let stark_proof = prove(&components.provers(), channel, commitment_scheme)?;
+        span.exit();
+
+        Ok((
+            Sha256Proof {
+                claimed_sum,
+                stark_proof,
+            },
+            public_data,
+        ))
+    }
+}
+
+/// Verify a SHA-256 proof over `2^log_size` rows, real and padding, against its public data.
+///
+/// The transcript is replayed in the same order as in [`prove_sha256_messages`]. The preprocessed
+/// trace is regenerated to get its column ids and sizes, and its commitment is recomputed and
+/// checked against the one of the proof. The lookup tables are always generated, never loaded from
+/// the on-disk cache of [`preprocessed::cache`], which the verifier does not trust.
+pub fn verify_sha256<MC: MerkleChannel>(
+    log_size: u32,
+    config: PcsConfig,
+    public_data: &PublicData,
+    proof: Sha256Proof<MC::H>,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    // Bounded before anything is allocated for the statement.
+    let max = max_log_size(config);
+    if !(LOG_N_LANES..=max).contains(&log_size) {
+        return Err(VerificationError::InvalidStructure(format!(
+            "Invalid log size {log_size}, expected between {LOG_N_LANES} and {max}"
+        )));
+    }
+    if public_data.digests.len() != public_data.n_blocks.len()
+        || public_data.n_blocks.contains(&0)
+        || public_data
+            .min_log_size()
+            .is_none_or(|min_log_size| min_log_size > log_size)
+        || public_data
+            .blocks
+            .as_ref()
+            .is_some_and(|blocks| Some(blocks.len()) != public_data.n_rows())
+    {
+        return Err(VerificationError::InvalidStructure(format!(
+            "Public data does not match log size {log_size}"
+        )));
+    }
+    if proof.stark_proof.commitments.len() < 3 {
+        return Err(VerificationError::InvalidStructure(
+            "Missing trace commitments".to_string(),
+        ));
+    }
+    let public_blocks = public_data.blocks.is_some();
 
     // Setup protocol.
-    let channel = &mut Blake2sChannel::default();
-    let mut commitment_scheme =
-        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
-
-    // Preprocessed trace.
-    let span = span!(Level::INFO, "Constant").entered();
-    let span_1 = span!(Level::INFO, "Simd generation").entered();
-    let preprocessed_trace = PreProcessedTrace::new(log_size);
-    span_1.exit();
-    let span_2 = span!(Level::INFO, "Extend evals").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(preprocessed_trace.trace);
-    tree_builder.commit(channel);
-    span_2.exit();
-    span.exit();
+    let channel = &mut MC::C::default();
+    config.mix_into(channel);
+    let commitment_scheme = &mut CommitmentSchemeVerifier::<MC>::new(config);
+
+    // Preprocessed trace, generated without the cache.
+    let preprocessed_trace = PreProcessedTrace::new_uncached(log_size);
+    let preprocessed_log_sizes = preprocessed_trace
+        .trace
+        .iter()
+        .map(|eval| eval.domain.log_size())
+        .collect::<Vec<_>>();
+    if proof.stark_proof.commitments[0]
+        != preprocessed_root::<MC>(log_size, config, preprocessed_trace.trace)
+    {
+        return Err(VerificationError::InvalidStructure(
+            "Preprocessed trace commitment does not match the lookup tables".to_string(),
+        ));
+    }
+    commitment_scheme.commit(
+        proof.stark_proof.commitments[0],
+        &preprocessed_log_sizes,
+        channel,
+    );
+
+    // Column sizes do not depend on the lookup elements.
+    let log_sizes = TreeVec::concat_cols(
+        components::Components::new(
+            log_size,
+            &mut TraceLocationAllocator::new_with_preprocessed_columns(&preprocessed_trace.ids),
+            &Relations::dummy(),
+            &proof.claimed_sum,
+            public_blocks,
+        )
+        .trace_log_degree_bounds()
+        .into_iter(),
+    );
 
     // Trace.
-    let span = span!(Level::INFO, "Trace").entered();
-    let (trace, lookup_data) = gen_trace(log_size);
-    let span_1 = span!(Level::INFO, "Extend evals").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(trace);
-    tree_builder.commit(channel);
-    span_1.exit();
-    span.exit();
+    commitment_scheme.commit(proof.stark_proof.commitments[1], &log_sizes[1], channel);
+
+    // Public data.
+    public_data.mix_into(channel);
 
     // Draw lookup elements.
     let relations = Relations::draw(channel);
 
     // Interaction trace.
-    let span = span!(Level::INFO, "Interaction").entered();
-    let (trace, claimed_sum) = gen_interaction_trace(lookup_data, &relations);
-    let span_1 = span!(Level::INFO, "Extend evals").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(trace);
-    tree_builder.commit(channel);
-    span_1.exit();
-    span.exit();
-
-    debug!(
-        "Columns count: {:?}",
-        commitment_scheme
-            .trees
-            .as_ref()
-            .map(|tree| tree.polynomials.len())
-    );
-    debug!(
-        "Columns length: {:?}",
-        commitment_scheme.trees.as_ref().map(|tree| {
-            let max_len = tree
-                .polynomials
-                .iter()
-                .map(|poly| poly.evals.values.len().ilog2())
-                .collect::<Vec<_>>()
-                .iter()
-                .copied()
-                .max()
-                .unwrap();
-            assert!(max_len <= log_size + 1);
-            max_len
-        })
-    );
+    proof.claimed_sum.mix_into(channel);
+    commitment_scheme.commit(proof.stark_proof.commitments[2], &log_sizes[2], channel);
 
-    // Prove constraints.
-    let span = span!(Level::INFO, "Prove").entered();
+    let total_sum = proof.claimed_sum.sum() + public_data.logup_sum(&relations, log_size);
+    if total_sum != SecureField::zero() {
+        return Err(VerificationError::InvalidStructure(format!(
+            "Relation summary is not zero: {total_sum}"
+        )));
+    }
+
+    // Verify constraints.
     let trace_allocator =
         &mut TraceLocationAllocator::new_with_preprocessed_columns(&preprocessed_trace.ids);
-    let components =
-        components::Components::new(log_size, trace_allocator, &relations, &claimed_sum);
+    let components = components::Components::new(
+        log_size,
+        trace_allocator,
+        &relations,
+        &proof.claimed_sum,
+        public_blocks,
+    );
+    verify(
+        &components.components(),
+        channel,
+        commitment_scheme,
+        proof.stark_proof,
+    )
+}
 
-    #[cfg(feature = "track-relations")]
-    println!(
-        "Trace log degree bounds: {:?}",
-        components.trace_log_degree_bounds()
+/// Merkle root of the preprocessed trace, as committed by the provers.
+///
+/// The preprocessed trace is part of the statement: the verifier recomputes its root rather than
+/// trusting the one of the proof, at the cost of one commitment.
+pub fn preprocessed_root<MC: MerkleChannel>(
+    log_size: u32,
+    config: PcsConfig,
+    preprocessed_trace: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> <MC::H as MerkleHasher>::Hash
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + 2)
+            .circle_domain()
+            .half_coset,
     );
+    commit_preprocessed::<MC>(config, &twiddles, preprocessed_trace)
+        .commitment
+        .root()
+}
+
+/// Interpolate, extend and commit the preprocessed trace, as the first tree of a proof.
+fn commit_preprocessed<MC: MerkleChannel>(
+    config: PcsConfig,
+    twiddles: &TwiddleTree<SimdBackend>,
+    preprocessed_trace: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> CommitmentTreeProver<SimdBackend, MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let mut commitment_scheme = CommitmentSchemeProver::<_, MC>::new(config, twiddles);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(preprocessed_trace);
+    tree_builder.commit(&mut MC::C::default());
+    commitment_scheme.trees.0.pop().unwrap()
+}
+
+/// Spans charged with their allocations by the `span-alloc` feature.
+pub const ALLOC_SPANS: &[&str] = &[
+    "Precompute twiddles",
+    "Constant",
+    "Trace",
+    "Scheduling",
+    "Compression",
+    "Preprocessed",
+    "Interaction",
+    "Prove",
+];
 
-    if claimed_sum.scheduling + claimed_sum.compression + claimed_sum.preprocessed.sum()
-        != SecureField::zero()
+/// Peak memory usage in bytes: tracked by the allocator with the `peak-alloc` or `span-alloc`
+/// features, else the peak resident set size on Linux.
+pub fn peak_memory_bytes() -> Option<u64> {
+    #[cfg(feature = "peak-alloc")]
     {
-        #[cfg(feature = "track-relations")]
-        println!(
-            "Relation summary: {:?}",
-            components.track_relations(&commitment_scheme)
-        );
-        panic!(
-            "Relation summary is not zero: {}",
-            claimed_sum.scheduling + claimed_sum.compression + claimed_sum.preprocessed.sum()
-        );
+        Some(PEAK_ALLOC.peak_usage() as u64)
     }
-
-    let proof = prove(&components.provers(), channel, commitment_scheme);
-    if let Err(e) = proof {
-        panic!("Proof error: {e:?}");
+    #[cfg(feature = "span-alloc")]
+    {
+        Some(utils::alloc::peak_bytes())
+    }
+    #[cfg(not(any(feature = "peak-alloc", feature = "span-alloc")))]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let kb = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kb * 1024)
     }
-    span.exit();
-
-    proof.unwrap()
 }
 
-pub fn print_enabled_features() {
-    let features: Vec<&str> = vec![
+pub fn enabled_features() -> Vec<&'static str> {
+    vec![
         #[cfg(feature = "parallel")]
         "Stwo parallel",
         #[cfg(not(feature = "parallel"))]
@@ -188,8 +667,21 @@ pub fn print_enabled_features() {
         "peak-alloc",
         #[cfg(feature = "jemalloc")]
         "jemalloc",
-    ];
+        #[cfg(feature = "mimalloc")]
+        "mimalloc",
+        #[cfg(feature = "smalloc")]
+        "smalloc",
+        #[cfg(feature = "rpmalloc")]
+        "rpmalloc",
+        #[cfg(feature = "snmalloc")]
+        "snmalloc",
+        #[cfg(feature = "span-alloc")]
+        "span-alloc",
+    ]
+}
 
+pub fn print_enabled_features() {
+    let features = enabled_features();
     if features.is_empty() {
         info!("Features: (none)");
     } else {
@@ -201,9 +693,17 @@ mod tests {
     use std::{env, time::Instant};
 
     use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    use stwo::core::vcs::{
+        blake2_merkle::Blake2sMerkleChannel, poseidon252_merkle::Poseidon252MerkleChannel,
+    };
 
     use super::*;
 
+    const PUBLIC_BLOCKS: ProverOptions = ProverOptions {
+        public_blocks: true,
+        track_relations: false,
+    };
+
     /// Print all enabled features
 
     #[test_log::test]
@@ -229,14 +729,16 @@ mod tests {
         let span = span!(Level::INFO, "Prove").entered();
 
         let start = Instant::now();
+        let prover =
+            Sha256Prover::<Blake2sMerkleChannel>::new(log_size, PcsConfig::default()).unwrap();
         (0..n_iter)
             .into_par_iter()
-            .map(|_| prove_sha256(log_size, PcsConfig::default()))
+            .map(|_| prover.prove().unwrap())
             .collect::<Vec<_>>();
         span.exit();
         info!(
             "Throughput {:?}",
-            (1 << log_n_instances) as f32 * n_iter as f32 / start.elapsed().as_secs() as f32
+            (1 << log_n_instances) as f64 * n_iter as f64 / start.elapsed().as_secs_f64()
         );
 
         #[cfg(feature = "peak-alloc")]
@@ -245,4 +747,342 @@ mod tests {
             info!("Peak memory: {} MB", peak_bytes);
         }
     }
+
+    #[test_log::test]
+    fn test_prove_blocks_digests() {
+        use sha2::{Digest, Sha256};
+
+        let input = b"hello world";
+
+        // Build padded "hello world" message (11 bytes)
+        let mut msg = Vec::from(input);
+        msg.push(0x80);
+        msg.resize(56, 0x00);
+        msg.extend_from_slice(&(input.len() as u64 * 8).to_be_bytes());
+        let block: [u32; 16] =
+            std::array::from_fn(|i| u32::from_be_bytes(msg[4 * i..4 * i + 4].try_into().unwrap()));
+
+        let blocks = vec![block; 1 << 13];
+        let (proof, digests) = prove_sha256_blocks::<Blake2sMerkleChannel>(
+            &blocks,
+            PUBLIC_BLOCKS,
+            PcsConfig::default(),
+        )
+        .unwrap();
+
+        let reference = Sha256::digest(input);
+        assert_eq!(digests.len(), blocks.len());
+        for digest in &digests {
+            let result = digest
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect::<Vec<u8>>();
+            assert_eq!(reference[..], result[..]);
+        }
+
+        let public_data = PublicData {
+            digests,
+            n_blocks: vec![1; blocks.len()],
+            blocks: Some(blocks),
+        };
+        verify_sha256::<Blake2sMerkleChannel>(13, PcsConfig::default(), &public_data, proof)
+            .unwrap();
+    }
+
+    #[test_log::test]
+    fn test_prove_blocks_padding() {
+        let config = PcsConfig::default();
+        let blocks = gen_blocks(4)[..5].to_vec();
+        let (proof, digests) =
+            prove_sha256_blocks::<Blake2sMerkleChannel>(&blocks, PUBLIC_BLOCKS, config).unwrap();
+        assert_eq!(digests, sha256::compress_blocks(&blocks));
+
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: Some(blocks),
+        };
+        assert_eq!(public_data.min_log_size(), Some(LOG_N_LANES));
+        assert_eq!(public_data.n_padding_rows(LOG_N_LANES), Some(11));
+        verify_sha256::<Blake2sMerkleChannel>(LOG_N_LANES, config, &public_data, proof.clone())
+            .unwrap();
+
+        // Claiming a padding row as a real one
+        let mut tampered_data = public_data.clone();
+        tampered_data
+            .digests
+            .push(sha256::process_chunk(messages::padding_block(), sha256::H));
+        tampered_data.n_blocks.push(1);
+        tampered_data.blocks = None;
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_N_LANES, config, &tampered_data, proof)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_prover_errors() {
+        let config = PcsConfig::default();
+
+        let result = prove_sha256::<Blake2sMerkleChannel>(LOG_N_LANES - 1, config);
+        assert!(matches!(
+            result,
+            Err(Sha256ProverError::InvalidLogSize { .. })
+        ));
+
+        let result = prove_sha256::<Blake2sMerkleChannel>(max_log_size(config) + 1, config);
+        assert!(matches!(
+            result,
+            Err(Sha256ProverError::InvalidLogSize { .. })
+        ));
+
+        let messages = vec![messages::pad_message(b"hello world"), vec![]];
+        let result = prove_sha256_messages::<Blake2sMerkleChannel>(
+            &messages,
+            ProverOptions::default(),
+            config,
+        );
+        assert!(matches!(result, Err(Sha256ProverError::InvalidInput(_))));
+    }
+
+    #[test_log::test]
+    fn test_prover_session() {
+        let config = PcsConfig::default();
+        let prover = Sha256Prover::<Blake2sMerkleChannel>::new(LOG_N_LANES + 1, config).unwrap();
+
+        // Batches smaller than the session are padded up to its size.
+        let proofs = (0..4u32)
+            .into_par_iter()
+            .map(|i| {
+                let messages = vec![messages::pad_message(&i.to_le_bytes()); 1 + i as usize];
+                prover.prove_messages(&messages, PUBLIC_BLOCKS).unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (proof, public_data) in proofs {
+            assert_eq!(public_data.min_log_size(), Some(LOG_N_LANES));
+            verify_sha256::<Blake2sMerkleChannel>(
+                prover.log_size(),
+                config,
+                &public_data,
+                proof.clone(),
+            )
+            .unwrap();
+            // The padding rows depend on the log size.
+            assert!(verify_sha256::<Blake2sMerkleChannel>(
+                prover.log_size() + 1,
+                config,
+                &public_data,
+                proof
+            )
+            .is_err());
+        }
+
+        let result = prover.prove_blocks(&gen_blocks(LOG_N_LANES + 2), ProverOptions::default());
+        assert!(matches!(result, Err(Sha256ProverError::InvalidInput(_))));
+    }
+
+    #[test_log::test]
+    fn test_track_relations() {
+        let mut rows = Rows::from_blocks(&gen_blocks(LOG_N_LANES));
+        rows.digests[3][0] ^= 1;
+        let options = ProverOptions {
+            track_relations: true,
+            ..Default::default()
+        };
+        let result = Sha256Prover::<Blake2sMerkleChannel>::new(LOG_N_LANES, PcsConfig::default())
+            .unwrap()
+            .prove_rows(rows, options);
+        let Err(Sha256ProverError::UnbalancedRelation {
+            components,
+            diagnostics: Some(diagnostics),
+            ..
+        }) = result
+        else {
+            panic!("Expected unbalanced relation diagnostics");
+        };
+        assert_eq!(components, ["compression", "verifier"]);
+
+        // The digest emitted by compression, and the wrong one consumed by the verifier
+        assert_eq!(diagnostics.imbalances.len(), 2);
+        let mut uses = diagnostics
+            .imbalances
+            .iter()
+            .map(|imbalance| {
+                assert_eq!(imbalance.relation, "chain");
+                assert_eq!(imbalance.uses.len(), 1);
+                let relation_use = &imbalance.uses[0];
+                (
+                    relation_use.component.as_str(),
+                    relation_use.row,
+                    relation_use.multiplicity,
+                )
+            })
+            .collect::<Vec<_>>();
+        uses.sort();
+        assert_eq!(uses, vec![("compression", 3, 1), ("verifier", 3, -1)]);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&diagnostics.to_json().unwrap()).unwrap();
+        assert_eq!(json["imbalances"].as_array().unwrap().len(), 2);
+    }
+
+    #[test_log::test]
+    fn test_verify_sha256_poseidon252() {
+        let config = PcsConfig::default();
+        let blocks = gen_blocks(LOG_N_LANES);
+        let (proof, digests) = prove_sha256_blocks::<Poseidon252MerkleChannel>(
+            &blocks,
+            ProverOptions::default(),
+            config,
+        )
+        .unwrap();
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: None,
+        };
+        verify_sha256::<Poseidon252MerkleChannel>(LOG_N_LANES, config, &public_data, proof)
+            .unwrap();
+    }
+
+    #[test_log::test]
+    fn test_prove_messages_digests() {
+        use sha2::{Digest, Sha256};
+
+        const LOG_SIZE: u32 = 13;
+        let config = PcsConfig::default();
+
+        // 2^LOG_SIZE blocks in total, over messages of 1 to 16 blocks
+        let mut messages: Vec<Vec<u8>> = vec![];
+        let mut n_rows = 0;
+        while n_rows < 1 << LOG_SIZE {
+            let len = (messages.len() * 61) % 1000;
+            messages.push((0..len).map(|i| (i + messages.len()) as u8).collect());
+            n_rows += messages::pad_message(messages.last().unwrap()).len();
+        }
+        while n_rows > 1 << LOG_SIZE {
+            let last = messages.pop().unwrap();
+            n_rows -= messages::pad_message(&last).len();
+        }
+        messages.extend(vec![vec![]; (1 << LOG_SIZE) - n_rows]);
+
+        let padded = messages
+            .iter()
+            .map(|message| messages::pad_message(message))
+            .collect::<Vec<_>>();
+        let (proof, public_data) =
+            prove_sha256_messages::<Blake2sMerkleChannel>(&padded, PUBLIC_BLOCKS, config).unwrap();
+
+        assert_eq!(public_data.digests.len(), messages.len());
+        for (message, digest) in messages.iter().zip(&public_data.digests) {
+            let result = digest
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect::<Vec<u8>>();
+            assert_eq!(Sha256::digest(message)[..], result[..]);
+        }
+
+        verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, proof.clone())
+            .unwrap();
+
+        // Moving a block boundary between two messages
+        let mut tampered_data = public_data.clone();
+        let i = tampered_data.n_blocks.iter().position(|n| *n > 1).unwrap();
+        tampered_data.n_blocks[i] -= 1;
+        tampered_data.n_blocks[i + 1] += 1;
+        assert!(verify_sha256::<Blake2sMerkleChannel>(
+            LOG_SIZE,
+            config,
+            &tampered_data,
+            proof.clone()
+        )
+        .is_err());
+
+        // Swapping two blocks of a message
+        let mut tampered_data = public_data.clone();
+        let first_block = tampered_data.n_blocks[..i].iter().sum::<u32>() as usize;
+        tampered_data
+            .blocks
+            .as_mut()
+            .unwrap()
+            .swap(first_block, first_block + 1);
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &tampered_data, proof).is_err()
+        );
+    }
+
+    #[test_log::test]
+    fn test_verify_sha256() {
+        use num_traits::One;
+
+        const LOG_SIZE: u32 = 13;
+        let config = PcsConfig::default();
+        let (proof, digests) = prove_sha256_blocks::<Blake2sMerkleChannel>(
+            &gen_blocks(LOG_SIZE),
+            ProverOptions::default(),
+            config,
+        )
+        .unwrap();
+        let public_data = PublicData {
+            n_blocks: vec![1; digests.len()],
+            digests,
+            blocks: None,
+        };
+
+        verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, proof.clone())
+            .unwrap();
+
+        // Unbalanced claimed sum
+        let mut tampered = proof.clone();
+        tampered.claimed_sum.scheduling += SecureField::one();
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, tampered)
+                .is_err()
+        );
+
+        // Balanced but wrong claimed sums
+        let mut tampered = proof.clone();
+        tampered.claimed_sum.scheduling += SecureField::one();
+        tampered.claimed_sum.compression -= SecureField::one();
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, tampered)
+                .is_err()
+        );
+
+        // Preprocessed trace committed to other lookup tables
+        let mut tampered = proof.clone();
+        tampered.stark_proof.0.commitments[0] = tampered.stark_proof.0.commitments[1];
+        assert!(matches!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, tampered),
+            Err(VerificationError::InvalidStructure(err)) if err.contains("Preprocessed")
+        ));
+
+        // Swapped commitments
+        let mut tampered = proof.clone();
+        tampered.stark_proof.0.commitments.swap(1, 2);
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &public_data, tampered)
+                .is_err()
+        );
+
+        // Wrong digest
+        let mut tampered_data = public_data.clone();
+        tampered_data.digests[0][0] ^= 1;
+        assert!(verify_sha256::<Blake2sMerkleChannel>(
+            LOG_SIZE,
+            config,
+            &tampered_data,
+            proof.clone()
+        )
+        .is_err());
+
+        // Claiming public blocks that were not proven
+        let tampered_data = PublicData {
+            blocks: Some(gen_blocks(LOG_SIZE)),
+            ..public_data
+        };
+        assert!(
+            verify_sha256::<Blake2sMerkleChannel>(LOG_SIZE, config, &tampered_data, proof).is_err()
+        );
+    }
 }
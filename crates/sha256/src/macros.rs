@@ -19,9 +19,11 @@ macro_rules! components {
         use stwo::core::pcs::TreeVec;
         use stwo::core::air::Component;
         use stwo_constraint_framework::{
-            relation_tracker::{add_to_relation_entries, RelationTrackerEntry},
+            relation_tracker::add_to_relation_entries,
+            FrameworkEval,
             TraceLocationAllocator,
         };
+        use $crate::diagnostics::ComponentEntries;
 
         components!(@gen_structs $( ($module, $name) ),+);
         components!(@gen_fns $( ($module, $name) ),+);
@@ -33,6 +35,7 @@ macro_rules! components {
             $( pub ${concat($module, _, $name)}: Vec<Vec<u32x16>>, )+
         }
 
+        #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
         pub struct ClaimedSum {
             $( pub ${concat($module, _, $name)}: SecureField, )+
         }
@@ -41,10 +44,14 @@ macro_rules! components {
             pub fn sum(&self) -> SecureField {
                 SecureField::zero() $( + self.${concat($module, _, $name)} )+
             }
+
+            pub fn to_vec(&self) -> Vec<SecureField> {
+                vec![ $( self.${concat($module, _, $name)}, )+ ]
+            }
         }
 
         pub struct Components {
-            $( pub ${concat($module, _, $name)}: $module::$name::air::Component, )+
+            $( pub ${concat($module, _, $name)}: $module::$name::Component, )+
         }
     };
 
@@ -56,7 +63,7 @@ macro_rules! components {
         ) -> Traces {
             Traces {
                 $(
-                    ${concat($module, _, $name)}: $module::$name::witness::gen_trace(
+                    ${concat($module, _, $name)}: $module::$name::gen_trace(
                         log_size,
                         scheduling_lookup_data,
                         compression_lookup_data
@@ -76,7 +83,7 @@ macro_rules! components {
 
             $(
                 let (trace, ${concat($module, _, $name, _, claimed_sum)}) =
-                    $module::$name::witness::gen_interaction_trace(&traces.${concat($module, _, $name)}, relations);
+                    $module::$name::gen_interaction_trace(&traces.${concat($module, _, $name)}, relations);
                 interaction_trace.extend(trace);
             )+
             (
@@ -124,9 +131,9 @@ macro_rules! components {
             ) -> Self {
                 Self {
                     $(
-                        ${concat($module, _, $name)}: $module::$name::air::Component::new(
+                        ${concat($module, _, $name)}: $module::$name::Component::new(
                             location_allocator,
-                            $module::$name::air::Eval { log_size, relations: relations.clone() },
+                            $module::$name::Eval { log_size, relations: relations.clone() },
                             claimed_sum.${concat($module, _, $name)},
                         ),
                     )+
@@ -137,14 +144,23 @@ macro_rules! components {
                 vec![ $(&self.${concat($module, _, $name)},)+ ]
             }
 
+            pub fn components(&self) -> Vec<&dyn Component> {
+                vec![ $(&self.${concat($module, _, $name)},)+ ]
+            }
+
             pub fn relation_entries(
                 &self,
                 trace: &TreeVec<Vec<&Vec<BaseField>>>,
-            ) -> Vec<RelationTrackerEntry> {
-                itertools::chain!(
-                    $( add_to_relation_entries(&self.${concat($module, _, $name)}, trace) ),+
-                )
-                .collect()
+            ) -> Vec<ComponentEntries> {
+                vec![
+                    $(
+                        ComponentEntries {
+                            component: concat!(stringify!($module), "::", stringify!($name)).to_string(),
+                            log_size: self.${concat($module, _, $name)}.log_size(),
+                            entries: add_to_relation_entries(&self.${concat($module, _, $name)}, trace),
+                        },
+                    )+
+                ]
             }
 
             pub fn trace_log_degree_bounds(&self) -> Vec<TreeVec<ColumnVec<u32>>> {
@@ -152,6 +168,366 @@ macro_rules! components {
                     $( self.${concat($module, _, $name)}.trace_log_degree_bounds(), )+
                 ]
             }
+
+            pub fn stats(
+                &self,
+                relation_paths: &std::collections::HashMap<&'static str, String>,
+            ) -> Vec<$crate::stats::ComponentStats> {
+                vec![
+                    $(
+                        $crate::stats::ComponentStats::new(
+                            concat!(stringify!($module), "::", stringify!($name)),
+                            &self.${concat($module, _, $name)},
+                            relation_paths,
+                        ),
+                    )+
+                ]
+            }
         }
     };
 }
+
+/// Declare a component looking up the rows of a preprocessed table.
+///
+/// ```ignore
+/// preprocessed_component! {
+///     table: sigma_0::Sigma0O2Columns[Sigma0I0I1Columns::SIZE],
+///     log_size: Sigma0::O2.count_ones() * 2,
+///     lookups: scheduling,
+///     relations: [
+///         o2_mult: sigma_0.o2(o2_0, o2_1, o2_low, o2_high)
+///             from |sigma_0_o20_pext, sigma_0_o21_pext|
+///                 (sigma_0_o20_pext << Sigma0::O2.count_ones()) + sigma_0_o21_pext,
+///     ],
+/// }
+/// ```
+///
+/// `table` names the columns of a table of `crate::preprocessed` and their offset in it,
+/// `log_size` is the log size of the table and `lookups` one of `scheduling`, `compression` or
+/// `feed_forward`. Each relation declares its multiplicity column, the table columns it combines
+/// and how the lookup columns of a round index the table.
+///
+/// A table looked up from several kinds of rounds omits `lookups` and lists, for each relation,
+/// the lookups it counts along with their index:
+///
+/// ```ignore
+/// preprocessed_component! {
+///     table: range_check_add::RangeCheckAddColumns[0],
+///     log_size: 19,
+///     relations: [
+///         carry_4_mult: range_check_add.add_4(value, carry_4) from [
+///             scheduling |new_w_low, carry_low| (new_w_low << 3) + carry_low,
+///             feed_forward |out_low, carry_low| (out_low << 3) + carry_low,
+///         ],
+///     ],
+/// }
+/// ```
+///
+/// The component expands to the `Component`, `Eval`, `gen_trace` and `gen_interaction_trace`
+/// items used by [`components!`](crate::components), along with a test of its constraints.
+#[macro_export]
+macro_rules! preprocessed_component {
+    (
+        table: $table:ident :: $columns:ident [ $offset:expr ],
+        log_size: $log_size:expr,
+        lookups: $lookups:ident,
+        relations: [
+            $(
+                $mult:ident : $relation:ident . $name:ident ( $( $col:ident ),+ $(,)? )
+                    from | $( $input:ident ),+ | $index:expr
+            ),+ $(,)?
+        ] $(,)?
+    ) => {
+        $crate::preprocessed_component! {
+            table: $table::$columns[$offset],
+            log_size: $log_size,
+            relations: [
+                $(
+                    $mult: $relation.$name($( $col ),+)
+                        from [$lookups |$( $input ),+| $index]
+                ),+
+            ],
+        }
+    };
+    (
+        table: $table:ident :: $columns:ident [ $offset:expr ],
+        log_size: $log_size:expr,
+        relations: [
+            $(
+                $mult:ident : $relation:ident . $name:ident ( $( $col:ident ),+ $(,)? )
+                    from [ $( $lookups:ident | $( $input:ident ),+ | $index:expr ),+ $(,)? ]
+            ),+ $(,)?
+        ] $(,)?
+    ) => {
+        /// Log size of the table.
+        pub const LOG_SIZE: u32 = $log_size;
+        const _: () = assert!(
+            LOG_SIZE <= $crate::partitions::MAX_TABLE_LOG_SIZE,
+            "Table too large for the masks"
+        );
+        /// Index of the first column of the component in the table.
+        const OFFSET: usize = $offset;
+
+        utils::trace_columns!(ComponentColumns, $( $mult ),+);
+
+        pub type Component = stwo_constraint_framework::FrameworkComponent<Eval>;
+
+        fn eval_constraints<E: stwo_constraint_framework::EvalAtRow>(
+            eval: &mut E,
+            relations: &$crate::relations::Relations,
+            log_size: u32,
+        ) {
+            let chunk_count = 1 << LOG_SIZE.saturating_sub(log_size);
+            for chunk in 0..chunk_count {
+                let mults = ComponentColumnsOwned::<E::F>::from_eval(eval);
+                let table =
+                    $crate::preprocessed::$table::${concat($columns, Owned)}::<E::F>::from_ids(
+                        eval,
+                        Some(chunk),
+                    );
+                $(
+                    utils::add_to_relation!(
+                        eval,
+                        relations.$relation.$name,
+                        E::EF::from(mults.$mult),
+                        $( table.$col ),+
+                    );
+                )+
+            }
+
+            eval.finalize_logup_in_pairs();
+        }
+
+        #[derive(Clone)]
+        pub struct Eval {
+            pub log_size: u32,
+            pub relations: $crate::relations::Relations,
+        }
+        impl stwo_constraint_framework::FrameworkEval for Eval {
+            fn log_size(&self) -> u32 {
+                LOG_SIZE.min(self.log_size)
+            }
+            fn max_constraint_log_degree_bound(&self) -> u32 {
+                LOG_SIZE.min(self.log_size) + 1
+            }
+            fn evaluate<E: stwo_constraint_framework::EvalAtRow>(&self, mut eval: E) -> E {
+                eval_constraints(&mut eval, &self.relations, self.log_size);
+                eval
+            }
+        }
+
+        pub fn gen_trace(
+            log_size: u32,
+            scheduling_lookup_data: &[Vec<std::simd::u32x16>],
+            compression_lookup_data: &[Vec<std::simd::u32x16>],
+        ) -> Vec<Vec<std::simd::u32x16>> {
+            // Unused by the tables looked up from the compression rounds only
+            let _ = scheduling_lookup_data;
+
+            // Dense counters for each relation, filled in parallel over the rows
+            let multiplicities = utils::simd::par_histograms(
+                [$( $crate::preprocessed_component!(@replace $mult, 1 << LOG_SIZE) ),+],
+                compression_lookup_data[0].len(),
+                |rows, [$( $mult ),+]| {
+                    $($(
+                        let rounds = $crate::preprocessed_component!(
+                            @rounds $lookups, scheduling_lookup_data, compression_lookup_data
+                        );
+                        for cols in rounds {
+                            for row in rows.clone() {
+                                $( let $input = cols.$input[row]; )+
+                                let index: std::simd::u32x16 = $index;
+                                index
+                                    .to_array()
+                                    .iter()
+                                    .for_each(|x| $mult[*x as usize] += 1);
+                            }
+                        }
+                    )+)+
+                },
+            );
+
+            $crate::components::preprocessed::lookup::split_multiplicities(
+                log_size,
+                multiplicities,
+            )
+        }
+
+        pub fn gen_interaction_trace(
+            trace: &[Vec<std::simd::u32x16>],
+            relations: &$crate::relations::Relations,
+        ) -> (
+            stwo::core::ColumnVec<
+                stwo::prover::poly::circle::CircleEvaluation<
+                    stwo::prover::backend::simd::SimdBackend,
+                    stwo::core::fields::m31::BaseField,
+                    stwo::prover::poly::BitReversedOrder,
+                >,
+            >,
+            stwo::core::fields::qm31::QM31,
+        ) {
+            use stwo_constraint_framework::Relation;
+
+            let preprocessed_columns = $crate::preprocessed::$table::gen_column_simd();
+            let table = $crate::preprocessed::$table::$columns::from_slice(
+                &preprocessed_columns
+                    [OFFSET..OFFSET + $crate::preprocessed::$table::$columns::SIZE],
+            );
+
+            $crate::components::preprocessed::lookup::gen_interaction_trace(
+                trace,
+                [$( utils::combine!(relations.$relation.$name, [$( table.$col ),+]) ),+],
+            )
+        }
+
+        #[cfg(test)]
+        mod air_tests {
+            use std::simd::u32x16;
+
+            use itertools::Itertools;
+            use stwo::{
+                core::{
+                    fields::{m31::BaseField, qm31::QM31},
+                    pcs::TreeVec,
+                    poly::circle::CanonicCoset,
+                },
+                prover::{
+                    backend::simd::{m31::LOG_N_LANES, SimdBackend},
+                    poly::{circle::CircleEvaluation, BitReversedOrder},
+                },
+            };
+            use stwo_constraint_framework::assert_constraints_on_polys;
+            use utils::circle_evaluation_u32x16;
+
+            use super::{eval_constraints, gen_interaction_trace, gen_trace, OFFSET};
+            use $crate::{
+                components::{
+                    compression::witness::gen_trace as gen_compression_trace,
+                    mutation::{self, Mutation},
+                    scheduling::witness::gen_trace as gen_scheduling_trace,
+                },
+                preprocessed::$table,
+                relations::Relations,
+            };
+
+            type Traces = TreeVec<Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>;
+
+            /// Multiplicities, traces and claimed sum of the component.
+            fn gen_traces(relations: &Relations) -> (Vec<Vec<u32x16>>, Traces, QM31) {
+                const LOG_N_SHA256: u32 = 8;
+
+                let (scheduling_trace, scheduling_lookup_data) =
+                    gen_scheduling_trace(LOG_N_SHA256);
+                let (_, compression_lookup_data) = gen_compression_trace(&scheduling_trace);
+                let max_log_size = 10;
+                let trace = gen_trace(
+                    max_log_size,
+                    &scheduling_lookup_data,
+                    &compression_lookup_data,
+                );
+                let simd_size = trace[0].len();
+
+                let (interaction_trace, claimed_sum) = gen_interaction_trace(&trace, relations);
+
+                let preprocessed_columns = $table::gen_column_simd();
+                let preprocessed_trace = $table::$columns::from_slice(
+                    &preprocessed_columns[OFFSET..OFFSET + $table::$columns::SIZE],
+                )
+                .chunks(simd_size)
+                .into_iter()
+                .flat_map(|c| c.iter().map(|c| circle_evaluation_u32x16!(c)))
+                .collect::<Vec<_>>();
+
+                let traces = TreeVec::new(vec![
+                    preprocessed_trace,
+                    trace
+                        .iter()
+                        .map(|c| circle_evaluation_u32x16!(c))
+                        .collect::<Vec<_>>(),
+                    interaction_trace,
+                ]);
+                (trace, traces, claimed_sum)
+            }
+
+            #[test_log::test]
+            fn test_constraints() {
+                let relations = Relations::dummy();
+                let (trace, traces, claimed_sum) = gen_traces(&relations);
+                let log_size = trace[0].len().ilog2() + LOG_N_LANES;
+
+                let trace_polys =
+                    traces.map(|trace| trace.into_iter().map(|c| c.interpolate()).collect_vec());
+
+                assert_constraints_on_polys(
+                    &trace_polys,
+                    CanonicCoset::new(log_size),
+                    |mut eval| {
+                        eval_constraints(&mut eval, &relations, log_size);
+                    },
+                    claimed_sum,
+                );
+            }
+
+            /// A wrong multiplicity, with the interaction trace regenerated from it, changes the
+            /// claimed sum of the component or fails its constraints.
+            #[test]
+            fn test_mutations() {
+                let relations = Relations::dummy();
+                let (trace, traces, claimed_sum) = gen_traces(&relations);
+                let log_size = trace[0].len().ilog2() + LOG_N_LANES;
+                let n_columns = trace.len();
+                let polys = traces.map(|trace| {
+                    trace.into_iter().map(|c| c.interpolate()).collect_vec()
+                });
+
+                let undetected = mutation::columns(n_columns, [0, 1, n_columns - 1])
+                    .into_iter()
+                    .map(|column| Mutation::spread(column, trace[column].len() << LOG_N_LANES))
+                    .filter(|mutated| {
+                        let mut trace = trace.clone();
+                        let column = &mut trace[mutated.column];
+                        column[mutated.row >> LOG_N_LANES].as_mut_array()
+                            [mutated.row % (1 << LOG_N_LANES)] += 1;
+                        let (interaction_trace, mutated_sum) =
+                            gen_interaction_trace(&trace, &relations);
+                        if mutated_sum != claimed_sum {
+                            return false;
+                        }
+
+                        let mut polys = polys.clone();
+                        polys[1][mutated.column] =
+                            circle_evaluation_u32x16!(trace[mutated.column]).interpolate();
+                        polys[2] = interaction_trace
+                            .into_iter()
+                            .map(|c| c.interpolate())
+                            .collect();
+                        !mutation::panics(|| {
+                            assert_constraints_on_polys(
+                                &polys,
+                                CanonicCoset::new(log_size),
+                                |mut eval| {
+                                    eval_constraints(&mut eval, &relations, log_size);
+                                },
+                                mutated_sum,
+                            )
+                        })
+                    })
+                    .collect_vec();
+                mutation::assert_detected(module_path!(), &undetected);
+            }
+        }
+    };
+
+    (@rounds scheduling, $scheduling:ident, $compression:ident) => {
+        $crate::components::preprocessed::lookup::scheduling_rounds($scheduling)
+    };
+    (@rounds compression, $scheduling:ident, $compression:ident) => {
+        $crate::components::preprocessed::lookup::compression_rounds($compression)
+    };
+    (@rounds feed_forward, $scheduling:ident, $compression:ident) => {
+        $crate::components::preprocessed::lookup::feed_forward_rounds($compression)
+    };
+    (@replace $_token:tt, $value:expr) => {
+        $value
+    };
+}
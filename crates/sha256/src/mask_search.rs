@@ -0,0 +1,358 @@
+//! Search of the I0/I1 input partitions used by the sigma lookups, see [`crate::partitions`].
+//!
+//! A sigma function XORs rotations (and optionally a right shift) of its input, so each output bit
+//! depends on a fixed set of input bits. Splitting the input bits into I0 and I1, each part is
+//! looked up in its own table and the output bits depending on both parts (O2) are recombined
+//! with an XOR table. The bitwise functions sharing the same input (maj for Σ0, ch for Σ1) are
+//! looked up over the same parts, split further into bytes when a table would be too large.
+//!
+//! The search starts from lattice masks, as in `design.py`, and from pseudo-random masks, and
+//! improves each of them by flipping and swapping bits until no move reduces the cost.
+
+use std::{collections::BTreeSet, fmt::Write};
+
+use rayon::prelude::*;
+
+use crate::partitions::{
+    self, BigSigma0, BigSigma1, Sigma0, Sigma1, SigmaMasks, MAX_TABLE_LOG_SIZE,
+};
+
+/// Number of pseudo-random starting masks, on top of the lattice ones.
+const N_RANDOM_SEEDS: usize = 64;
+
+/// Rotation and shift amounts of a sigma function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigmaSpec {
+    pub rotations: Vec<u32>,
+    pub shift: Option<u32>,
+}
+
+impl SigmaSpec {
+    /// Output bits depending on at least one bit of `input`.
+    pub fn output_bits(&self, input: u32) -> u32 {
+        partitions::output_bits(input, &self.rotations, self.shift)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchParams {
+    pub spec: SigmaSpec,
+    /// Number of operands of each bitwise function looked up over the parts, e.g. `[3]` for maj
+    /// and `[2, 2]` for ch (left and right).
+    pub arities: Vec<u32>,
+    /// Maximum log size of a single table.
+    pub max_log_size: u32,
+}
+
+/// Size of a set of lookup tables, ordered by rows first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TableCost {
+    pub rows: u64,
+    pub columns: usize,
+}
+
+impl TableCost {
+    fn add(&mut self, log_size: u32, columns: usize) {
+        self.rows = self
+            .rows
+            .saturating_add(1u64.checked_shl(log_size).unwrap_or(u64::MAX));
+        self.columns += columns;
+    }
+}
+
+/// An I0/I1 partition with its output bits and tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub i0: u32,
+    pub i1: u32,
+    pub o0: u32,
+    pub o1: u32,
+    pub o2: u32,
+    /// Whether the 16-bit halves are split into bytes for the bitwise lookups.
+    pub bytes: bool,
+    pub cost: TableCost,
+    /// Sum of the log sizes in excess of the limit, over all the tables.
+    pub excess: u32,
+}
+
+impl Split {
+    pub fn new(params: &SearchParams, i0: u32) -> Self {
+        // Invalid splits are costed too, their tables in excess of the limit
+        let SigmaMasks { i0, i1, o0, o1, o2 } =
+            SigmaMasks::new_unchecked(i0, &params.spec.rotations, params.spec.shift);
+        let max_arity = params.arities.iter().copied().max().unwrap_or(0);
+        let bytes = [i0, i1]
+            .into_iter()
+            .flat_map(halves)
+            .any(|half| max_arity * half.count_ones() > params.max_log_size);
+
+        let mut split = Self {
+            i0,
+            i1,
+            o0,
+            o1,
+            o2,
+            bytes,
+            cost: TableCost::default(),
+            excess: 0,
+        };
+        for part in [i0, i1] {
+            let pieces = split.pieces(part);
+            split.add_table(params, part.count_ones(), pieces.len() + 3);
+            for piece in pieces {
+                for &arity in &params.arities {
+                    split.add_table(params, arity * piece.count_ones(), arity as usize + 1);
+                }
+            }
+        }
+        split.add_table(params, 2 * o2.count_ones(), 4);
+        split
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.excess == 0
+    }
+
+    /// Pieces of a part looked up separately, in place in the 32-bit word.
+    fn pieces(&self, part: u32) -> Vec<u32> {
+        if self.bytes {
+            (0..4).map(|i| part & 0xFF << (8 * i)).collect()
+        } else {
+            vec![part & 0xFFFF, part & 0xFFFF0000]
+        }
+    }
+
+    fn add_table(&mut self, params: &SearchParams, log_size: u32, columns: usize) {
+        self.cost.add(log_size, columns);
+        self.excess += log_size.saturating_sub(params.max_log_size);
+    }
+
+    fn score(&self) -> (u32, TableCost, u32) {
+        (self.excess, self.cost, self.i0)
+    }
+
+    /// The module of [`crate::partitions`] deriving the masks from I0, checked at compile time
+    /// against the sigma function at `sigma` if given.
+    pub fn to_rust(&self, name: &str, spec: &SigmaSpec, sigma: Option<&str>) -> String {
+        let mut out =
+            format!("#[allow(non_snake_case)]\npub mod {name} {{\n    use super::SigmaMasks;\n\n");
+        writeln!(
+            out,
+            "    pub const ROTATIONS: &[u32] = &{:?};",
+            spec.rotations
+        )
+        .unwrap();
+        writeln!(out, "    pub const SHIFT: Option<u32> = {:?};", spec.shift).unwrap();
+        if let Some(sigma) = sigma {
+            writeln!(out, "    assert_sigma!({sigma});").unwrap();
+        }
+        writeln!(
+            out,
+            "\n    const MASKS: SigmaMasks = SigmaMasks::new(0b{:032b}, ROTATIONS, SHIFT);\n",
+            self.i0
+        )
+        .unwrap();
+
+        let mut push = |name: String, value: String| {
+            writeln!(out, "    pub const {name}: u32 = {value};").unwrap();
+        };
+        push("I0".to_string(), "MASKS.i0".to_string());
+        push("I1".to_string(), "MASKS.i1".to_string());
+        for k in 0..2 {
+            for (side, half) in [("L", "& 0xFFFF"), ("H", ">> 16")] {
+                push(format!("I{k}_{side}"), format!("I{k} {half}"));
+                if self.bytes {
+                    push(format!("I{k}_{side}0"), format!("I{k}_{side} & 0xFF"));
+                    push(format!("I{k}_{side}1"), format!("I{k}_{side} >> 8"));
+                }
+            }
+        }
+        for k in 0..3 {
+            push(format!("O{k}"), format!("MASKS.o{k}"));
+        }
+        for (side, half) in [("L", "& 0xFFFF"), ("H", ">> 16")] {
+            for k in 0..3 {
+                push(format!("O{k}_{side}"), format!("O{k} {half}"));
+            }
+        }
+        out.push('}');
+        out
+    }
+}
+
+const fn halves(x: u32) -> [u32; 2] {
+    [x & 0xFFFF, x >> 16]
+}
+
+/// Search the valid split with the lowest cost, if any.
+pub fn search(params: &SearchParams) -> Option<Split> {
+    seeds(&params.spec)
+        .into_par_iter()
+        .map(|seed| descend(params, seed))
+        .min_by_key(Split::score)
+        .filter(Split::is_valid)
+}
+
+/// Starting masks: the lattices spanned by the differences of the amounts, all their rotations,
+/// and pseudo-random masks.
+fn seeds(spec: &SigmaSpec) -> BTreeSet<u32> {
+    let amounts: Vec<u32> = spec.rotations.iter().chain(&spec.shift).copied().collect();
+    let mut seeds = BTreeSet::new();
+    for (i, &origin) in amounts.iter().enumerate() {
+        let steps: Vec<u32> = amounts
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &n)| (n + 32 - origin) % 32)
+            .take(2)
+            .collect();
+        for a_len in 1..=6 {
+            for b_len in 1..=6 {
+                let lattice = (0..a_len)
+                    .flat_map(|a| (0..b_len).map(move |b| (a, b)))
+                    .fold(0u32, |acc, (a, b)| {
+                        let bit =
+                            steps.first().map_or(0, |s| s * a) + steps.get(1).map_or(0, |s| s * b);
+                        acc | 1 << (bit % 32)
+                    });
+                seeds.extend((0..32).map(|r| lattice.rotate_left(r)));
+            }
+        }
+    }
+
+    // xorshift64
+    let mut state = 0x2545F4914F6CDD1Du64;
+    seeds.extend((0..N_RANDOM_SEEDS).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u32
+    }));
+    seeds
+}
+
+/// Flip or swap bits of `i0` as long as it lowers the cost.
+fn descend(params: &SearchParams, i0: u32) -> Split {
+    let mut best = Split::new(params, i0);
+    loop {
+        let i0 = best.i0;
+        let flips = (0..32).map(|i| 1u32 << i);
+        let swaps = (0..32).filter(|i| i0 >> i & 1 == 1).flat_map(|i| {
+            (0..32)
+                .filter(|j| i0 >> j & 1 == 0)
+                .map(move |j| 1 << i | 1 << j)
+        });
+        let candidate = flips
+            .chain(swaps)
+            .map(|mask| Split::new(params, i0 ^ mask))
+            .min_by_key(Split::score)
+            .unwrap();
+        if candidate.score() >= best.score() {
+            break;
+        }
+        best = candidate;
+    }
+
+    // Name the part holding bit 0 I0, as in the current masks.
+    if best.i0 & 1 == 0 {
+        best = Split::new(params, best.i1);
+    }
+    best
+}
+
+/// Search parameters, current I0 mask and path of the SHA-256 sigma functions, by module name.
+pub fn sha256_presets() -> [(&'static str, SearchParams, u32, &'static str); 4] {
+    let params = |rotations: &[u32], shift, arities: &[u32]| SearchParams {
+        spec: SigmaSpec {
+            rotations: rotations.to_vec(),
+            shift,
+        },
+        arities: arities.to_vec(),
+        max_log_size: MAX_TABLE_LOG_SIZE,
+    };
+    [
+        (
+            "Sigma0",
+            params(Sigma0::ROTATIONS, Sigma0::SHIFT, &[]),
+            Sigma0::I0,
+            "crate::sha256::small_sigma_0",
+        ),
+        (
+            "Sigma1",
+            params(Sigma1::ROTATIONS, Sigma1::SHIFT, &[]),
+            Sigma1::I0,
+            "crate::sha256::small_sigma_1",
+        ),
+        (
+            "BigSigma0",
+            params(BigSigma0::ROTATIONS, BigSigma0::SHIFT, &[3]),
+            BigSigma0::I0,
+            "crate::sha256::big_sigma_0",
+        ),
+        (
+            "BigSigma1",
+            params(BigSigma1::ROTATIONS, BigSigma1::SHIFT, &[2, 2]),
+            BigSigma1::I0,
+            "crate::sha256::big_sigma_1",
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_masks() {
+        let outputs = [
+            [Sigma0::O0, Sigma0::O1, Sigma0::O2],
+            [Sigma1::O0, Sigma1::O1, Sigma1::O2],
+            [BigSigma0::O0, BigSigma0::O1, BigSigma0::O2],
+            [BigSigma1::O0, BigSigma1::O1, BigSigma1::O2],
+        ];
+        let partitions = include_str!("partitions.rs");
+        for ((name, params, i0, sigma), outputs) in sha256_presets().into_iter().zip(outputs) {
+            let split = Split::new(&params, i0);
+            assert!(split.is_valid(), "{name}");
+            assert_eq!([split.o0, split.o1, split.o2], outputs, "{name}");
+            assert!(
+                partitions.contains(&split.to_rust(name, &params.spec, Some(sigma))),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_rust() {
+        let (name, params, i0, _) = &sha256_presets()[2];
+        let rust = Split::new(params, *i0).to_rust(name, &params.spec, None);
+        assert!(rust.starts_with(
+            "#[allow(non_snake_case)]\npub mod BigSigma0 {\n    use super::SigmaMasks;\n\n"
+        ));
+        assert!(rust.contains("    pub const ROTATIONS: &[u32] = &[2, 13, 22];\n"));
+        assert!(rust.contains("    pub const SHIFT: Option<u32> = None;\n"));
+        assert!(!rust.contains("assert_sigma!"));
+        assert!(rust.contains(&format!(
+            "    const MASKS: SigmaMasks = SigmaMasks::new(0b{:032b}, ROTATIONS, SHIFT);\n",
+            BigSigma0::I0
+        )));
+        assert!(rust.contains("    pub const I0_H0: u32 = I0_H & 0xFF;\n"));
+        assert!(rust.contains("    pub const O2_L: u32 = O2 & 0xFFFF;\n"));
+        // Only I0 is a literal, everything else derives from it.
+        assert_eq!(rust.matches("0b").count(), 1);
+        assert_eq!(
+            rust.lines()
+                .filter(|line| line.contains("pub const"))
+                .count(),
+            25
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let [(_, params, i0, _), ..] = sha256_presets();
+        let split = search(&params).unwrap();
+        assert!(split.is_valid());
+        assert!(split.cost <= Split::new(&params, i0).cost);
+    }
+}
@@ -0,0 +1,160 @@
+//! Messages laid out one block per row.
+//!
+//! Each row compresses its block from the state left by the previous block of the same message,
+//! or from `H` for the first block. The trace is filled up to a power of two number of rows with
+//! padding messages, each a single block of the empty message, which come after the real ones.
+
+use stwo::prover::backend::simd::m31::N_LANES;
+
+use crate::sha256::{compress_blocks, process_chunk, H};
+
+/// Pad a message and split it into big-endian 512-bit blocks.
+pub fn pad_message(message: &[u8]) -> Vec<[u32; 16]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x80);
+    while !(bytes.len() + 8).is_multiple_of(64) {
+        bytes.push(0x00);
+    }
+    bytes.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+    bytes
+        .array_chunks::<64>()
+        .map(|chunk| {
+            std::array::from_fn(|i| u32::from_be_bytes(chunk[4 * i..4 * i + 4].try_into().unwrap()))
+        })
+        .collect()
+}
+
+/// Padded empty message, filling the unused rows of the trace.
+pub fn padding_block() -> [u32; 16] {
+    pad_message(&[])[0]
+}
+
+/// Number of rows of the trace for `n_rows` real rows, `None` if it overflows.
+pub fn padded_len(n_rows: usize) -> Option<usize> {
+    n_rows.max(N_LANES).checked_next_power_of_two()
+}
+
+#[derive(Clone, Debug)]
+pub struct Rows {
+    pub blocks: Vec<[u32; 16]>,
+    /// Index of the message of each row.
+    pub msg_ids: Vec<u32>,
+    /// Index of the block of each row in its message.
+    pub block_indices: Vec<u32>,
+    /// State each block is compressed from.
+    pub states: Vec<[u32; 8]>,
+    /// Digest of each message.
+    pub digests: Vec<[u32; 8]>,
+}
+
+impl Rows {
+    pub fn new(messages: &[Vec<[u32; 16]>]) -> Self {
+        let n_rows = messages.iter().map(Vec::len).sum();
+        let mut rows = Self {
+            blocks: Vec::with_capacity(n_rows),
+            msg_ids: Vec::with_capacity(n_rows),
+            block_indices: Vec::with_capacity(n_rows),
+            states: Vec::with_capacity(n_rows),
+            digests: Vec::with_capacity(messages.len()),
+        };
+
+        for (msg_id, message) in messages.iter().enumerate() {
+            assert!(!message.is_empty(), "Message {msg_id} has no block");
+            let mut state = H;
+            for (block_idx, block) in message.iter().enumerate() {
+                rows.blocks.push(*block);
+                rows.msg_ids.push(msg_id as u32);
+                rows.block_indices.push(block_idx as u32);
+                rows.states.push(state);
+                state = process_chunk(*block, state);
+            }
+            rows.digests.push(state);
+        }
+
+        rows
+    }
+
+    /// One single-block message per row.
+    pub fn from_blocks(blocks: &[[u32; 16]]) -> Self {
+        Self {
+            blocks: blocks.to_vec(),
+            msg_ids: (0..blocks.len() as u32).collect(),
+            block_indices: vec![0; blocks.len()],
+            states: vec![H; blocks.len()],
+            digests: compress_blocks(blocks),
+        }
+    }
+
+    /// Append padding messages up to `2^log_size` rows.
+    pub fn pad(&mut self, log_size: u32) {
+        let block = padding_block();
+        let digest = process_chunk(block, H);
+        for _ in self.len()..1 << log_size {
+            self.blocks.push(block);
+            self.msg_ids.push(self.digests.len() as u32);
+            self.block_indices.push(0);
+            self.states.push(H);
+            self.digests.push(digest);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Number of blocks of each message.
+    pub fn n_blocks(&self) -> Vec<u32> {
+        let mut n_blocks = vec![0; self.digests.len()];
+        self.msg_ids
+            .iter()
+            .for_each(|msg_id| n_blocks[*msg_id as usize] += 1);
+        n_blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    #[test]
+    fn test_rows_digests() {
+        let messages: Vec<Vec<u8>> = vec![
+            b"hello world".to_vec(),
+            vec![0xab; 55],
+            vec![0xcd; 56],
+            (0..=255).cycle().take(1000).collect(),
+        ];
+        let rows = Rows::new(
+            &messages
+                .iter()
+                .map(|message| pad_message(message))
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(rows.n_blocks(), vec![1, 1, 2, 16]);
+        let mut padded = rows.clone();
+        padded.pad(5);
+        assert_eq!(padded.len(), 32);
+        assert_eq!(padded.n_blocks()[..4], [1, 1, 2, 16]);
+        assert_eq!(padded.n_blocks()[4..], [1; 12]);
+
+        for (message, digest) in messages
+            .iter()
+            .chain(std::iter::repeat_n(&vec![], 12))
+            .zip(padded.digests)
+        {
+            let result = digest
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect::<Vec<u8>>();
+            assert_eq!(Sha256::digest(message)[..], result[..]);
+        }
+    }
+}
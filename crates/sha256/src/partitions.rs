@@ -19,88 +19,200 @@ pub enum Partition {
     ADD7,
 }
 
+/// Output bits of a sigma function, given by its rotation and shift amounts, depending on at least
+/// one bit of `input`.
+pub const fn output_bits(input: u32, rotations: &[u32], shift: Option<u32>) -> u32 {
+    let mut out = 0;
+    let mut i = 0;
+    while i < rotations.len() {
+        out |= input.rotate_right(rotations[i]);
+        i += 1;
+    }
+    if let Some(shift) = shift {
+        out |= input >> shift;
+    }
+    out
+}
+
+/// Maximum log size of a lookup table built on the masks.
+pub const MAX_TABLE_LOG_SIZE: u32 = 21;
+
+/// Partition of the input of a sigma function into I0 and I1, and of its output into the bits
+/// depending only on I0 (O0), only on I1 (O1) or on both (O2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigmaMasks {
+    pub i0: u32,
+    pub i1: u32,
+    pub o0: u32,
+    pub o1: u32,
+    pub o2: u32,
+}
+
+impl SigmaMasks {
+    pub const fn new(i0: u32, rotations: &[u32], shift: Option<u32>) -> Self {
+        let masks = Self::new_unchecked(i0, rotations, shift);
+
+        // The I0, I1 and O2 tables are indexed by the bits of I0, I1 and two values of O2.
+        assert!(
+            masks.i0.count_ones() <= MAX_TABLE_LOG_SIZE
+                && masks.i1.count_ones() <= MAX_TABLE_LOG_SIZE,
+            "I0 or I1 table too large"
+        );
+        assert!(
+            2 * masks.o2.count_ones() <= MAX_TABLE_LOG_SIZE,
+            "O2 table too large"
+        );
+        masks
+    }
+
+    /// The masks of [`Self::new`], without checking the sizes of their tables.
+    pub const fn new_unchecked(i0: u32, rotations: &[u32], shift: Option<u32>) -> Self {
+        let i1 = !i0;
+        let out_0 = output_bits(i0, rotations, shift);
+        let out_1 = output_bits(i1, rotations, shift);
+        let o2 = out_0 & out_1;
+        let (o0, o1) = (out_0 & !o2, out_1 & !o2);
+        Self { i0, i1, o0, o1, o2 }
+    }
+}
+
+/// Parameters the masks of a sigma function are derived from, telling apart the lookup tables
+/// built on different masks, see [`crate::preprocessed::cache`].
+pub fn mask_params(i0: u32, rotations: &[u32], shift: Option<u32>) -> Vec<u32> {
+    [&[i0][..], rotations, &[shift.unwrap_or(u32::MAX)]].concat()
+}
+
+/// Assert at compile time that `$sigma` is the function of the `ROTATIONS` and `SHIFT` in scope.
+/// Sigma functions are linear, so it is enough to compare them on each bit.
+macro_rules! assert_sigma {
+    ($sigma:path) => {
+        const _: () = {
+            let mut bit = 0;
+            while bit < 32 {
+                assert!(
+                    $sigma(1 << bit) == super::output_bits(1 << bit, ROTATIONS, SHIFT),
+                    "ROTATIONS and SHIFT must match the sigma function"
+                );
+                bit += 1;
+            }
+        };
+    };
+}
+
 #[allow(non_snake_case)]
 pub mod Sigma0 {
-    pub const I0: u32 = 0b01001010101010101011010101010101;
-    pub const I1: u32 = 0b10110101010101010100101010101010;
-    pub const I0_L: u32 = 0b1011010101010101;
-    pub const I1_L: u32 = 0b0100101010101010;
-    pub const I0_H: u32 = 0b0100101010101010;
-    pub const I1_H: u32 = 0b1011010101010101;
-    pub const O0: u32 = 0b10101000000101010101000000101010;
-    pub const O1: u32 = 0b01010000001010101010100000010101;
-    pub const O2: u32 = 0b00000111110000000000011111000000;
-    pub const O0_L: u32 = 0b0101000000101010;
-    pub const O1_L: u32 = 0b1010100000010101;
-    pub const O2_L: u32 = 0b0000011111000000;
-    pub const O0_H: u32 = 0b1010100000010101;
-    pub const O1_H: u32 = 0b0101000000101010;
-    pub const O2_H: u32 = 0b0000011111000000;
+    use super::SigmaMasks;
+
+    pub const ROTATIONS: &[u32] = &[7, 18];
+    pub const SHIFT: Option<u32> = Some(3);
+    assert_sigma!(crate::sha256::small_sigma_0);
+
+    const MASKS: SigmaMasks = SigmaMasks::new(0b01001010101010101011010101010101, ROTATIONS, SHIFT);
+
+    pub const I0: u32 = MASKS.i0;
+    pub const I1: u32 = MASKS.i1;
+    pub const I0_L: u32 = I0 & 0xFFFF;
+    pub const I0_H: u32 = I0 >> 16;
+    pub const I1_L: u32 = I1 & 0xFFFF;
+    pub const I1_H: u32 = I1 >> 16;
+    pub const O0: u32 = MASKS.o0;
+    pub const O1: u32 = MASKS.o1;
+    pub const O2: u32 = MASKS.o2;
+    pub const O0_L: u32 = O0 & 0xFFFF;
+    pub const O1_L: u32 = O1 & 0xFFFF;
+    pub const O2_L: u32 = O2 & 0xFFFF;
+    pub const O0_H: u32 = O0 >> 16;
+    pub const O1_H: u32 = O1 >> 16;
+    pub const O2_H: u32 = O2 >> 16;
 }
 
 #[allow(non_snake_case)]
 pub mod Sigma1 {
-    pub const I0: u32 = 0b10101011010101101010100001010101;
-    pub const I1: u32 = 0b01010100101010010101011110101010;
-    pub const I0_L: u32 = 0b1010100001010101;
-    pub const I1_L: u32 = 0b0101011110101010;
-    pub const I0_H: u32 = 0b1010101101010110;
-    pub const I1_H: u32 = 0b0101010010101001;
-    pub const O0: u32 = 0b01010100000010101001010100101010;
-    pub const O1: u32 = 0b00101010110101010000101000010100;
-    pub const O2: u32 = 0b10000001001000000110000011000001;
-    pub const O0_L: u32 = 0b1001010100101010;
-    pub const O1_L: u32 = 0b0000101000010100;
-    pub const O2_L: u32 = 0b0110000011000001;
-    pub const O0_H: u32 = 0b0101010000001010;
-    pub const O1_H: u32 = 0b0010101011010101;
-    pub const O2_H: u32 = 0b1000000100100000;
+    use super::SigmaMasks;
+
+    pub const ROTATIONS: &[u32] = &[17, 19];
+    pub const SHIFT: Option<u32> = Some(10);
+    assert_sigma!(crate::sha256::small_sigma_1);
+
+    const MASKS: SigmaMasks = SigmaMasks::new(0b10101011010101101010100001010101, ROTATIONS, SHIFT);
+
+    pub const I0: u32 = MASKS.i0;
+    pub const I1: u32 = MASKS.i1;
+    pub const I0_L: u32 = I0 & 0xFFFF;
+    pub const I0_H: u32 = I0 >> 16;
+    pub const I1_L: u32 = I1 & 0xFFFF;
+    pub const I1_H: u32 = I1 >> 16;
+    pub const O0: u32 = MASKS.o0;
+    pub const O1: u32 = MASKS.o1;
+    pub const O2: u32 = MASKS.o2;
+    pub const O0_L: u32 = O0 & 0xFFFF;
+    pub const O1_L: u32 = O1 & 0xFFFF;
+    pub const O2_L: u32 = O2 & 0xFFFF;
+    pub const O0_H: u32 = O0 >> 16;
+    pub const O1_H: u32 = O1 >> 16;
+    pub const O2_H: u32 = O2 >> 16;
 }
 
 #[allow(non_snake_case)]
 pub mod BigSigma0 {
-    pub const I0: u32 = 0b11110000011111000000111110000011;
-    pub const I1: u32 = 0b00001111100000111111000001111100;
-    pub const I0_L: u32 = 0b0000111110000011;
-    pub const I0_L0: u32 = 0b10000011;
-    pub const I0_L1: u32 = 0b00001111;
-    pub const I0_H: u32 = 0b1111000001111100;
-    pub const I0_H0: u32 = 0b01111100;
-    pub const I0_H1: u32 = 0b11110000;
-    pub const I1_L: u32 = 0b1111000001111100;
-    pub const I1_L0: u32 = 0b01111100;
-    pub const I1_L1: u32 = 0b11110000;
-    pub const I1_H: u32 = 0b0000111110000011;
-    pub const I1_H0: u32 = 0b10000011;
-    pub const I1_H1: u32 = 0b00001111;
-    pub const O0: u32 = 0b01110000000111100000001111000000;
-    pub const O1: u32 = 0b00000011110000000111000000011110;
-    pub const O2: u32 = 0b10001100001000011000110000100001;
-    pub const O0_L: u32 = 0b0000001111000000;
-    pub const O1_L: u32 = 0b0111000000011110;
-    pub const O2_L: u32 = 0b1000110000100001;
-    pub const O0_H: u32 = 0b0111000000011110;
-    pub const O1_H: u32 = 0b0000001111000000;
-    pub const O2_H: u32 = 0b1000110000100001;
+    use super::SigmaMasks;
+
+    pub const ROTATIONS: &[u32] = &[2, 13, 22];
+    pub const SHIFT: Option<u32> = None;
+    assert_sigma!(crate::sha256::big_sigma_0);
+
+    const MASKS: SigmaMasks = SigmaMasks::new(0b11110000011111000000111110000011, ROTATIONS, SHIFT);
+
+    pub const I0: u32 = MASKS.i0;
+    pub const I1: u32 = MASKS.i1;
+    pub const I0_L: u32 = I0 & 0xFFFF;
+    pub const I0_L0: u32 = I0_L & 0xFF;
+    pub const I0_L1: u32 = I0_L >> 8;
+    pub const I0_H: u32 = I0 >> 16;
+    pub const I0_H0: u32 = I0_H & 0xFF;
+    pub const I0_H1: u32 = I0_H >> 8;
+    pub const I1_L: u32 = I1 & 0xFFFF;
+    pub const I1_L0: u32 = I1_L & 0xFF;
+    pub const I1_L1: u32 = I1_L >> 8;
+    pub const I1_H: u32 = I1 >> 16;
+    pub const I1_H0: u32 = I1_H & 0xFF;
+    pub const I1_H1: u32 = I1_H >> 8;
+    pub const O0: u32 = MASKS.o0;
+    pub const O1: u32 = MASKS.o1;
+    pub const O2: u32 = MASKS.o2;
+    pub const O0_L: u32 = O0 & 0xFFFF;
+    pub const O1_L: u32 = O1 & 0xFFFF;
+    pub const O2_L: u32 = O2 & 0xFFFF;
+    pub const O0_H: u32 = O0 >> 16;
+    pub const O1_H: u32 = O1 >> 16;
+    pub const O2_H: u32 = O2 >> 16;
 }
 
 #[allow(non_snake_case)]
 pub mod BigSigma1 {
-    pub const I0: u32 = 0b10011000110001100110011000110001;
-    pub const I1: u32 = 0b01100111001110011001100111001110;
-    pub const I0_L: u32 = 0b0110011000110001;
-    pub const I0_H: u32 = 0b1001100011000110;
-    pub const I1_L: u32 = 0b1001100111001110;
-    pub const I1_H: u32 = 0b0110011100111001;
-    pub const O0: u32 = 0b01000010001000110001100010001000;
-    pub const O1: u32 = 0b00011000100011001110011000100011;
-    pub const O2: u32 = 0b10100101010100000000000101010100;
-    pub const O0_L: u32 = 0b0001100010001000;
-    pub const O1_L: u32 = 0b1110011000100011;
-    pub const O2_L: u32 = 0b0000000101010100;
-    pub const O0_H: u32 = 0b0100001000100011;
-    pub const O1_H: u32 = 0b0001100010001100;
-    pub const O2_H: u32 = 0b1010010101010000;
+    use super::SigmaMasks;
+
+    pub const ROTATIONS: &[u32] = &[6, 11, 25];
+    pub const SHIFT: Option<u32> = None;
+    assert_sigma!(crate::sha256::big_sigma_1);
+
+    const MASKS: SigmaMasks = SigmaMasks::new(0b10011000110001100110011000110001, ROTATIONS, SHIFT);
+
+    pub const I0: u32 = MASKS.i0;
+    pub const I1: u32 = MASKS.i1;
+    pub const I0_L: u32 = I0 & 0xFFFF;
+    pub const I0_H: u32 = I0 >> 16;
+    pub const I1_L: u32 = I1 & 0xFFFF;
+    pub const I1_H: u32 = I1 >> 16;
+    pub const O0: u32 = MASKS.o0;
+    pub const O1: u32 = MASKS.o1;
+    pub const O2: u32 = MASKS.o2;
+    pub const O0_L: u32 = O0 & 0xFFFF;
+    pub const O1_L: u32 = O1 & 0xFFFF;
+    pub const O2_L: u32 = O2 & 0xFFFF;
+    pub const O0_H: u32 = O0 >> 16;
+    pub const O1_H: u32 = O1 >> 16;
+    pub const O2_H: u32 = O2 >> 16;
 }
 
 /// Generates all subsets of the given bitmask `mask`
@@ -6,7 +6,8 @@ use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
 use crate::{
-    partitions::{pext_u32x16, BigSigma0 as BigSigma0Partitions, SubsetIterator},
+    partitions::{mask_params, pext_u32x16, BigSigma0 as BigSigma0Partitions, SubsetIterator},
+    preprocessed::cache,
     sha256::big_sigma_0_u32x16,
 };
 
@@ -83,7 +84,17 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    let params = mask_params(
+        BigSigma0Partitions::I0,
+        BigSigma0Partitions::ROTATIONS,
+        BigSigma0Partitions::SHIFT,
+    );
+    cache::cached("big_sigma_0", &params, gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     // I0 lookup
     let i0_tuples: Vec<(u32x16, u32x16, u32x16, u32x16, u32x16, u32x16)> =
         SubsetIterator::new(BigSigma0Partitions::I0)
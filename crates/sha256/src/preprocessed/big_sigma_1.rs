@@ -6,7 +6,8 @@ use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
 use crate::{
-    partitions::{pext_u32x16, BigSigma1 as BigSigma1Partitions, SubsetIterator},
+    partitions::{mask_params, pext_u32x16, BigSigma1 as BigSigma1Partitions, SubsetIterator},
+    preprocessed::cache,
     sha256::big_sigma_1_u32x16,
 };
 
@@ -81,7 +82,17 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    let params = mask_params(
+        BigSigma1Partitions::I0,
+        BigSigma1Partitions::ROTATIONS,
+        BigSigma1Partitions::SHIFT,
+    );
+    cache::cached("big_sigma_1", &params, gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     // I0 lookup
     let i0_tuples: Vec<(u32x16, u32x16, u32x16, u32x16, u32x16)> =
         SubsetIterator::new(BigSigma1Partitions::I0)
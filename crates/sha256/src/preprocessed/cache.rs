@@ -0,0 +1,339 @@
+//! Optional on-disk cache of the generated lookup tables.
+//!
+//! When a cache directory is set, each table is stored in its own file, named after the table,
+//! [`LAYOUT_VERSION`] and the [`fingerprint`] of the parameters the table is generated from, such
+//! as the masks of a sigma function. A file starts with a 64 bytes header followed by the columns,
+//! one after the other, in native byte order:
+//!
+//! | Offset | Size | Content                                 |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 8    | [`MAGIC`]                               |
+//! | 8      | 4    | Layout version, little-endian           |
+//! | 12     | 4    | Number of columns, little-endian        |
+//! | 16     | 8    | SIMD rows per column, little-endian     |
+//! | 24     | 32   | SHA-256 of the columns                  |
+//! | 56     | 8    | Fingerprint of the table, little-endian |
+//!
+//! Files are memory-mapped and checked against their checksum the first time a process loads them.
+//! The mapping is then kept for the lifetime of the process, and the columns are handed out as
+//! views into it. A missing, stale or corrupted file is regenerated and written again.
+//!
+//! The checksum is not keyed: it catches corruption, not someone with write access to the cache
+//! directory, who can replace a table and its checksum. The verifier bypasses the cache with
+//! [`uncached`].
+
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    simd::u32x16,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex, RwLock,
+    },
+};
+
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{debug, warn};
+use utils::simd::flatten_simd;
+
+/// Environment variable setting the cache directory at startup.
+pub const CACHE_DIR_ENV: &str = "SHA256_TABLE_CACHE_DIR";
+
+/// Version of the tables and of the file layout, bumped whenever either changes.
+pub const LAYOUT_VERSION: u32 = 2;
+
+pub const MAGIC: [u8; 8] = *b"SHA256TB";
+
+const HEADER_SIZE: usize = 64;
+
+static CACHE_DIR: LazyLock<RwLock<Option<PathBuf>>> =
+    LazyLock::new(|| RwLock::new(std::env::var_os(CACHE_DIR_ENV).map(PathBuf::from)));
+
+/// Files mapped and checked by this process.
+static MAPPED: LazyLock<Mutex<HashMap<PathBuf, &'static Mmap>>> = LazyLock::new(Default::default);
+
+thread_local! {
+    /// Whether [`cached`] generates the tables without the cache on this thread.
+    static BYPASS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Columns of a table, borrowed from a mapped file of the cache or generated.
+pub type TableColumns = Vec<Cow<'static, [u32x16]>>;
+
+#[derive(Debug, Error)]
+pub enum TableCacheError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid table file: {0}")]
+    Invalid(String),
+}
+
+/// Set the cache directory, or disable the cache with `None`.
+pub fn set_cache_dir(dir: Option<PathBuf>) {
+    *CACHE_DIR.write().unwrap() = dir;
+}
+
+/// Cache directory, initially read from [`CACHE_DIR_ENV`].
+pub fn cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.read().unwrap().clone()
+}
+
+/// Run `f` with the cache bypassed on the current thread: the tables loaded by `f` are generated,
+/// and neither read from nor written to the cache directory.
+pub fn uncached<T>(f: impl FnOnce() -> T) -> T {
+    /// Restores the previous state, even if `f` panics.
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BYPASS.set(self.0);
+        }
+    }
+
+    let _restore = Restore(BYPASS.replace(true));
+    f()
+}
+
+/// Fingerprint of the parameters a table is generated from.
+pub fn fingerprint(params: &[u32]) -> u64 {
+    let mut hasher = Sha256::new();
+    params
+        .iter()
+        .for_each(|param| hasher.update(param.to_le_bytes()));
+    u64::from_le_bytes(hasher.finalize()[..8].try_into().unwrap())
+}
+
+/// Path of the file of a table in the cache directory.
+pub fn table_path(dir: &Path, name: &str, fingerprint: u64) -> PathBuf {
+    dir.join(format!("{name}.v{LAYOUT_VERSION}.{fingerprint:016x}.bin"))
+}
+
+/// Load the columns of a table from the cache, or generate them and fill the cache.
+///
+/// `params` are the parameters the table is generated from: a file generated from other
+/// parameters is a cache miss. The cache is skipped inside [`uncached`].
+pub fn cached(
+    name: &str,
+    params: &[u32],
+    gen_columns: impl FnOnce() -> Vec<Vec<u32x16>>,
+) -> TableColumns {
+    cached_in(cache_dir().as_deref(), name, params, gen_columns)
+}
+
+/// [`cached`] in the cache directory `dir`.
+fn cached_in(
+    dir: Option<&Path>,
+    name: &str,
+    params: &[u32],
+    gen_columns: impl FnOnce() -> Vec<Vec<u32x16>>,
+) -> TableColumns {
+    let owned = |columns: Vec<Vec<u32x16>>| columns.into_iter().map(Cow::Owned).collect();
+    let Some(dir) = dir.filter(|_| !BYPASS.get()) else {
+        return owned(gen_columns());
+    };
+    let fingerprint = fingerprint(params);
+    let path = table_path(dir, name, fingerprint);
+
+    match load(&path, fingerprint) {
+        Ok(columns) => {
+            debug!("Loaded table {name} from {}", path.display());
+            return columns.into_iter().map(Cow::Borrowed).collect();
+        }
+        Err(TableCacheError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => warn!("Regenerating table {name}: {err}"),
+    }
+
+    let columns = gen_columns();
+    if let Err(err) = store(&path, fingerprint, &columns) {
+        warn!("Could not cache table {name}: {err}");
+    }
+    owned(columns)
+}
+
+/// Columns of a table file, borrowed from its mapping.
+///
+/// The file is only read and hashed the first time this process loads it, later loads only check
+/// the header of the mapping.
+pub fn load(path: &Path, fingerprint: u64) -> Result<Vec<&'static [u32x16]>, TableCacheError> {
+    let mut mapped = MAPPED.lock().unwrap();
+    if let Some(&mmap) = mapped.get(path) {
+        return columns(mmap, fingerprint);
+    }
+
+    let file = File::open(path)?;
+    // Safety: the file is only read, and written to a temporary file before being moved in place.
+    let mmap = unsafe { Mmap::map(&file)? };
+    columns(&mmap, fingerprint)?;
+    if Sha256::digest(&mmap[HEADER_SIZE..])[..] != mmap[24..56] {
+        return Err(TableCacheError::Invalid("checksum mismatch".to_string()));
+    }
+    let mmap: &'static Mmap = Box::leak(Box::new(mmap));
+    mapped.insert(path.to_path_buf(), mmap);
+    columns(mmap, fingerprint)
+}
+
+/// Check the header of a table file, and split its payload into columns.
+fn columns(bytes: &[u8], fingerprint: u64) -> Result<Vec<&[u32x16]>, TableCacheError> {
+    if bytes.len() < HEADER_SIZE || bytes[..8] != MAGIC {
+        return Err(TableCacheError::Invalid("missing header".to_string()));
+    }
+    let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+    if version != LAYOUT_VERSION {
+        return Err(TableCacheError::Invalid(format!(
+            "layout version {version}, expected {LAYOUT_VERSION}"
+        )));
+    }
+    let found = u64::from_le_bytes(bytes[56..64].try_into().unwrap());
+    if found != fingerprint {
+        return Err(TableCacheError::Invalid(format!(
+            "fingerprint {found:016x}, expected {fingerprint:016x}"
+        )));
+    }
+    let n_columns = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+    let n_rows = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+    let payload = &bytes[HEADER_SIZE..];
+    if Some(payload.len())
+        != n_rows
+            .checked_mul(size_of::<u32x16>())
+            .and_then(|size| size.checked_mul(n_columns))
+    {
+        return Err(TableCacheError::Invalid(format!(
+            "{} bytes for {n_columns} columns of {n_rows} rows",
+            payload.len()
+        )));
+    }
+
+    // The mapping is page aligned, and so is the payload for u32x16.
+    assert!(payload.as_ptr().cast::<u32x16>().is_aligned());
+    let values = unsafe {
+        std::slice::from_raw_parts(payload.as_ptr().cast::<u32x16>(), n_columns * n_rows)
+    };
+    Ok((0..n_columns)
+        .map(|i| &values[i * n_rows..(i + 1) * n_rows])
+        .collect())
+}
+
+/// Write the columns of a table file, through a temporary file so that readers never see it
+/// partially written.
+pub fn store(
+    path: &Path,
+    fingerprint: u64,
+    columns: &[impl AsRef<[u32x16]>],
+) -> Result<(), TableCacheError> {
+    static TMP_ID: AtomicU64 = AtomicU64::new(0);
+
+    let n_rows = columns.first().map_or(0, |column| column.as_ref().len());
+    if columns.iter().any(|column| column.as_ref().len() != n_rows) {
+        return Err(TableCacheError::Invalid(
+            "columns of different lengths".to_string(),
+        ));
+    }
+    let bytes = |column: &[u32x16]| {
+        let values = flatten_simd(column);
+        // Safety: any u32 is a valid sequence of 4 bytes.
+        unsafe { std::slice::from_raw_parts(values.as_ptr().cast::<u8>(), values.len() * 4) }
+    };
+
+    let mut hasher = Sha256::new();
+    columns
+        .iter()
+        .for_each(|column| hasher.update(bytes(column.as_ref())));
+    let mut header = [0u8; HEADER_SIZE];
+    header[..8].copy_from_slice(&MAGIC);
+    header[8..12].copy_from_slice(&LAYOUT_VERSION.to_le_bytes());
+    header[12..16].copy_from_slice(&(columns.len() as u32).to_le_bytes());
+    header[16..24].copy_from_slice(&(n_rows as u64).to_le_bytes());
+    header[24..56].copy_from_slice(&hasher.finalize());
+    header[56..64].copy_from_slice(&fingerprint.to_le_bytes());
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        TMP_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(&header)?;
+    for column in columns {
+        file.write_all(bytes(column.as_ref()))?;
+    }
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessed::sigma_0;
+
+    #[test]
+    fn test_store_load() {
+        let dir = std::env::temp_dir().join(format!("sha256-table-cache-{}", std::process::id()));
+        let fingerprint = fingerprint(&[1, 2, 3]);
+        let path = table_path(&dir, "sigma_0", fingerprint);
+        let columns = sigma_0::gen_column_simd();
+
+        store(&path, fingerprint, &columns).unwrap();
+        assert!(load(&path, fingerprint)
+            .unwrap()
+            .into_iter()
+            .eq(columns.iter().map(|c| &c[..])));
+        // Loaded again from the mapping
+        assert_eq!(load(&path, fingerprint).unwrap().len(), columns.len());
+
+        // Generated from other parameters
+        assert!(matches!(
+            load(&path, fingerprint + 1),
+            Err(TableCacheError::Invalid(_))
+        ));
+
+        // Flip a bit of the last value
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let corrupted_path = table_path(&dir, "corrupted", fingerprint);
+        fs::write(&corrupted_path, &bytes).unwrap();
+        assert!(matches!(
+            load(&corrupted_path, fingerprint),
+            Err(TableCacheError::Invalid(_))
+        ));
+
+        // Another layout version
+        *bytes.last_mut().unwrap() ^= 1;
+        bytes[8..12].copy_from_slice(&(LAYOUT_VERSION + 1).to_le_bytes());
+        let version_path = table_path(&dir, "version", fingerprint);
+        fs::write(&version_path, &bytes).unwrap();
+        assert!(matches!(
+            load(&version_path, fingerprint),
+            Err(TableCacheError::Invalid(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_uncached() {
+        let dir =
+            std::env::temp_dir().join(format!("sha256-table-uncached-{}", std::process::id()));
+        let gen_columns = || vec![vec![u32x16::splat(1)]];
+        // A tampered table, with a valid checksum
+        let path = table_path(&dir, "uncached", fingerprint(&[]));
+        store(&path, fingerprint(&[]), &[vec![u32x16::splat(2)]]).unwrap();
+
+        let bypassed = uncached(|| cached_in(Some(&dir), "uncached", &[], gen_columns));
+        assert!(matches!(bypassed[0], Cow::Owned(_)));
+        assert_eq!(bypassed[0][..], [u32x16::splat(1)]);
+        // Restored after the call
+        let loaded = cached_in(Some(&dir), "uncached", &[], gen_columns);
+        assert_eq!(loaded[0][..], [u32x16::splat(2)]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,7 +6,8 @@ use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
 use crate::{
-    partitions::{BigSigma1 as BigSigma1Partitions, SubsetIterator},
+    partitions::{mask_params, BigSigma1 as BigSigma1Partitions, SubsetIterator},
+    preprocessed::cache,
     sha256::ch_left_u32x16,
 };
 
@@ -82,7 +83,17 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    let params = mask_params(
+        BigSigma1Partitions::I0,
+        BigSigma1Partitions::ROTATIONS,
+        BigSigma1Partitions::SHIFT,
+    );
+    cache::cached("ch_left", &params, gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     let mut all_columns: Vec<Vec<u32x16>> = vec![Vec::new(); ChLeftColumns::SIZE];
 
     for (i, partition) in [
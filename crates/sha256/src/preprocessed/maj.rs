@@ -6,7 +6,8 @@ use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
 use crate::{
-    partitions::{BigSigma0 as BigSigma0Partitions, SubsetIterator},
+    partitions::{mask_params, BigSigma0 as BigSigma0Partitions, SubsetIterator},
+    preprocessed::cache,
     sha256::maj_u32x16,
 };
 
@@ -118,7 +119,17 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    let params = mask_params(
+        BigSigma0Partitions::I0,
+        BigSigma0Partitions::ROTATIONS,
+        BigSigma0Partitions::SHIFT,
+    );
+    cache::cached("maj", &params, gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     let mut all_columns: Vec<Vec<u32x16>> = vec![Vec::new(); MajColumns::SIZE];
 
     for (i, partition) in [
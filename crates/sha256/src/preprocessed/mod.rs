@@ -15,6 +15,7 @@ use utils::circle_evaluation_u32x16;
 
 pub mod big_sigma_0;
 pub mod big_sigma_1;
+pub mod cache;
 pub mod ch_left;
 pub mod ch_right;
 pub mod maj;
@@ -79,6 +80,12 @@ impl PreProcessedTrace {
 
         Self { trace, ids }
     }
+
+    /// [`Self::new`] without the on-disk cache of the tables, whose files are only checked
+    /// against an unkeyed checksum. Used by the verifier.
+    pub fn new_uncached(log_size: u32) -> Self {
+        cache::uncached(|| Self::new(log_size))
+    }
 }
 
 #[cfg(test)]
@@ -4,6 +4,8 @@ use stwo::core::channel::Channel;
 use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
+use crate::preprocessed::cache;
+
 // [value, carry]
 const N_COLUMNS: usize = 2;
 
@@ -38,7 +40,12 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    cache::cached("range_check_add", &[], gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     const N: usize = 1 << 15;
     let mut all_columns = vec![
         Vec::with_capacity(N),
@@ -6,7 +6,8 @@ use stwo_constraint_framework::relation;
 use utils::trace_columns;
 
 use crate::{
-    partitions::{pext_u32x16, Sigma1 as Sigma1Partitions, SubsetIterator},
+    partitions::{mask_params, pext_u32x16, Sigma1 as Sigma1Partitions, SubsetIterator},
+    preprocessed::cache,
     sha256::small_sigma_1_u32x16,
 };
 
@@ -77,7 +78,17 @@ impl Relation {
     }
 }
 
-pub fn gen_column_simd() -> Vec<Vec<u32x16>> {
+/// Columns of the lookup tables, from the cache if enabled, see [`cache`].
+pub fn gen_column_simd() -> cache::TableColumns {
+    let params = mask_params(
+        Sigma1Partitions::I0,
+        Sigma1Partitions::ROTATIONS,
+        Sigma1Partitions::SHIFT,
+    );
+    cache::cached("sigma_1", &params, gen_columns)
+}
+
+fn gen_columns() -> Vec<Vec<u32x16>> {
     // I0 lookup
     let i0_tuples: Vec<(u32x16, u32x16, u32x16, u32x16, u32x16)> =
         SubsetIterator::new(Sigma1Partitions::I0)
@@ -0,0 +1,203 @@
+//! Public statement of a SHA-256 proof.
+//!
+//! The compression component consumes the state each block starts from and emits the state it
+//! ends on, tagged with the message and block index. The verifier emits the initial hash value of
+//! each message and consumes its digest after its last block, so the chain only balances when
+//! every message is compressed block after block into the claimed digest. When the blocks are
+//! public, compression also emits each message block and the verifier consumes them.
+//!
+//! The rows after the real ones hold padding messages, see [`crate::messages`]. They are not part
+//! of the public data, the verifier accounts for them on its own.
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use stwo::core::{
+    channel::Channel,
+    fields::{m31::M31, qm31::SecureField, FieldExpOps},
+};
+use stwo_constraint_framework::{relation_tracker::RelationTrackerEntry, Relation};
+
+use crate::{
+    messages::{padded_len, padding_block},
+    relations::Relations,
+    sha256::{process_chunk, H},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicData {
+    /// Digest of each message.
+    pub digests: Vec<[u32; 8]>,
+    /// Number of blocks of each message.
+    pub n_blocks: Vec<u32>,
+    /// Message blocks, one per row in message order, if part of the statement.
+    pub blocks: Option<Vec<[u32; 16]>>,
+}
+
+impl PublicData {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.digests.len() as u64);
+        channel.mix_u32s(&self.digests.concat());
+        channel.mix_u32s(&self.n_blocks);
+        channel.mix_u64(self.blocks.is_some() as u64);
+        if let Some(blocks) = &self.blocks {
+            channel.mix_u32s(&blocks.concat());
+        }
+    }
+
+    /// Total number of blocks, that is the number of real rows of the trace, `None` if it
+    /// overflows.
+    pub fn n_rows(&self) -> Option<usize> {
+        self.n_blocks
+            .iter()
+            .try_fold(0usize, |acc, n| acc.checked_add(*n as usize))
+    }
+
+    /// Number of padding rows in a trace of `2^log_size` rows, following the real ones.
+    pub fn n_padding_rows(&self, log_size: u32) -> Option<usize> {
+        (1usize << log_size).checked_sub(self.n_rows()?)
+    }
+
+    /// Log size of the smallest trace holding the real rows.
+    pub fn min_log_size(&self) -> Option<u32> {
+        Some(padded_len(self.n_rows()?)?.ilog2())
+    }
+
+    /// Values emitted and consumed by the verifier, with the trace row they relate to.
+    ///
+    /// Panics if the rows do not fit a trace of `2^log_size` rows, [`crate::verify_sha256`]
+    /// rejects such public data beforehand.
+    fn entries(&self, log_size: u32) -> Vec<PublicEntry> {
+        // Padding messages are single blocks, right after the real messages
+        let n_padding_rows = self
+            .n_padding_rows(log_size)
+            .expect("Rows do not fit the trace");
+        let padding_block = padding_block();
+        let padding_digest = process_chunk(padding_block, H);
+        let digests = self
+            .digests
+            .iter()
+            .chain(std::iter::repeat_n(&padding_digest, n_padding_rows));
+        let n_blocks = self
+            .n_blocks
+            .iter()
+            .chain(std::iter::repeat_n(&1, n_padding_rows));
+        let blocks = self.blocks.as_ref().map(|blocks| {
+            blocks
+                .iter()
+                .chain(std::iter::repeat_n(&padding_block, n_padding_rows))
+        });
+
+        let mut entries = vec![];
+        let mut row = 0;
+        for (msg_id, (digest, n_blocks)) in digests.zip(n_blocks).enumerate() {
+            let n_blocks = *n_blocks as usize;
+            let tag = |block_idx: usize| [M31::from(msg_id as u32), M31::from(block_idx as u32)];
+            entries.push(PublicEntry {
+                relation: PublicRelation::Chain,
+                row,
+                multiplicity: M31::from(1),
+                values: [tag(0).to_vec(), to_limbs(&H)].concat(),
+            });
+            entries.push(PublicEntry {
+                relation: PublicRelation::Chain,
+                row: row + n_blocks - 1,
+                multiplicity: -M31::from(1),
+                values: [tag(n_blocks).to_vec(), to_limbs(digest)].concat(),
+            });
+            row += n_blocks;
+        }
+
+        if let Some(blocks) = blocks {
+            let block_ids = self
+                .n_blocks
+                .iter()
+                .chain(std::iter::repeat_n(&1, n_padding_rows))
+                .enumerate()
+                .flat_map(|(msg_id, n_blocks)| {
+                    (0..*n_blocks).map(move |block_idx| (msg_id, block_idx))
+                });
+            for (row, (block, (msg_id, block_idx))) in blocks.zip(block_ids).enumerate() {
+                entries.push(PublicEntry {
+                    relation: PublicRelation::Message,
+                    row,
+                    multiplicity: -M31::from(1),
+                    values: [
+                        vec![M31::from(msg_id as u32), M31::from(block_idx)],
+                        to_limbs(block),
+                    ]
+                    .concat(),
+                });
+            }
+        }
+
+        entries
+    }
+
+    /// Logup sum of the values emitted and consumed by the verifier, for a trace of
+    /// `2^log_size` rows.
+    pub fn logup_sum(&self, relations: &Relations, log_size: u32) -> SecureField {
+        self.entries(log_size)
+            .into_iter()
+            .map(|entry| {
+                let denom = match entry.relation {
+                    PublicRelation::Chain => {
+                        relations.chain.combine::<M31, SecureField>(&entry.values)
+                    }
+                    PublicRelation::Message => {
+                        relations.message.combine::<M31, SecureField>(&entry.values)
+                    }
+                };
+                SecureField::from(entry.multiplicity) * denom.inverse()
+            })
+            .fold(SecureField::zero(), |acc, x| acc + x)
+    }
+
+    /// Relation tracker entries of the verifier, with the trace row they relate to.
+    pub fn relation_entries(
+        &self,
+        relations: &Relations,
+        log_size: u32,
+    ) -> Vec<(usize, RelationTrackerEntry)> {
+        self.entries(log_size)
+            .into_iter()
+            .map(|entry| {
+                let relation = match entry.relation {
+                    PublicRelation::Chain => {
+                        Relation::<M31, SecureField>::get_name(&relations.chain)
+                    }
+                    PublicRelation::Message => {
+                        Relation::<M31, SecureField>::get_name(&relations.message)
+                    }
+                };
+                (
+                    entry.row,
+                    RelationTrackerEntry {
+                        relation: relation.to_string(),
+                        mult: entry.multiplicity,
+                        values: entry.values,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+enum PublicRelation {
+    Chain,
+    Message,
+}
+
+struct PublicEntry {
+    relation: PublicRelation,
+    row: usize,
+    multiplicity: M31,
+    values: Vec<M31>,
+}
+
+/// Split u32 words into (low, high) u16 limbs.
+fn to_limbs(words: &[u32]) -> Vec<M31> {
+    words
+        .iter()
+        .flat_map(|word| [M31::from(word & 0xffff), M31::from(word >> 16)])
+        .collect()
+}
@@ -1,4 +1,10 @@
-use stwo::core::channel::Channel;
+use std::collections::HashMap;
+
+use stwo::core::{
+    channel::Channel,
+    fields::{m31::M31, qm31::SecureField},
+};
+use stwo_constraint_framework::Relation;
 
 use crate::preprocessed::{
     big_sigma_0, big_sigma_1, ch_left, ch_right, maj, range_check_add, sigma_0, sigma_1,
@@ -8,7 +14,23 @@ mod w {
     use stwo_constraint_framework::relation;
 
     use crate::components::W_SIZE;
-    relation!(Relation, W_SIZE);
+    relation!(W, W_SIZE);
+}
+
+// [msg_id, block_idx, block]
+pub mod message {
+    use stwo_constraint_framework::relation;
+
+    use crate::sha256::CHUNK_SIZE;
+    relation!(MESSAGE, 2 + CHUNK_SIZE);
+}
+
+// [msg_id, block_idx, state]
+pub mod chain {
+    use stwo_constraint_framework::relation;
+
+    pub const STATE_SIZE: usize = 16; // 16 u16 = 8 u32
+    relation!(CHAIN, 2 + STATE_SIZE);
 }
 
 #[derive(Clone)]
@@ -21,7 +43,9 @@ pub struct Relations {
     pub ch_right: ch_right::Relation,
     pub maj: maj::Relation,
     pub range_check_add: range_check_add::Relation,
-    pub w: w::Relation,
+    pub w: w::W,
+    pub message: message::MESSAGE,
+    pub chain: chain::CHAIN,
 }
 
 impl Relations {
@@ -35,7 +59,9 @@ impl Relations {
             ch_right: ch_right::Relation::draw(channel),
             maj: maj::Relation::draw(channel),
             range_check_add: range_check_add::Relation::draw(channel),
-            w: w::Relation::draw(channel),
+            w: w::W::draw(channel),
+            message: message::MESSAGE::draw(channel),
+            chain: chain::CHAIN::draw(channel),
         }
     }
 
@@ -49,7 +75,74 @@ impl Relations {
             ch_right: ch_right::Relation::dummy(),
             maj: maj::Relation::dummy(),
             range_check_add: range_check_add::Relation::dummy(),
-            w: w::Relation::dummy(),
+            w: w::W::dummy(),
+            message: message::MESSAGE::dummy(),
+            chain: chain::CHAIN::dummy(),
+        }
+    }
+    /// Map each relation name, as reported by the relation tracker, to its path in [`Relations`].
+    pub fn names(&self) -> HashMap<String, String> {
+        self.paths()
+            .into_iter()
+            .map(|(name, _, path)| (name, path))
+            .collect()
+    }
+
+    /// Map the type name of each relation to its path in [`Relations`].
+    pub fn type_names(&self) -> HashMap<&'static str, String> {
+        self.paths()
+            .into_iter()
+            .map(|(_, type_name, path)| (type_name, path))
+            .collect()
+    }
+
+    /// Name, type name and path in [`Relations`] of each relation.
+    fn paths(&self) -> Vec<(String, &'static str, String)> {
+        macro_rules! paths {
+            ($($group:ident $(. $field:ident)?),+ $(,)?) => {
+                vec![$(
+                    (
+                        Relation::<M31, SecureField>::get_name(&self.$group $(. $field)?).to_string(),
+                        std::any::type_name_of_val(&self.$group $(. $field)?),
+                        [stringify!($group) $(, stringify!($field))?].join("::"),
+                    ),
+                )+]
+            };
         }
+
+        paths!(
+            sigma_0.i0,
+            sigma_0.i1,
+            sigma_0.o2,
+            sigma_1.i0,
+            sigma_1.i1,
+            sigma_1.o2,
+            big_sigma_0.i0,
+            big_sigma_0.i1,
+            big_sigma_0.o2,
+            big_sigma_1.i0,
+            big_sigma_1.i1,
+            big_sigma_1.o2,
+            ch_left.i0_low,
+            ch_left.i0_high,
+            ch_left.i1_low,
+            ch_left.i1_high,
+            ch_right.i0_low,
+            ch_right.i0_high,
+            ch_right.i1_low,
+            ch_right.i1_high,
+            maj.i0_low,
+            maj.i0_high_0,
+            maj.i0_high_1,
+            maj.i1_low_0,
+            maj.i1_low_1,
+            maj.i1_high,
+            range_check_add.add_4,
+            range_check_add.add_7,
+            range_check_add.add_8,
+            w,
+            message,
+            chain,
+        )
     }
 }
@@ -112,6 +112,23 @@ pub fn process_chunk_u32x16(chunk: [u32x16; 16], mut hash: [u32x16; 8]) -> [u32x
     hash
 }
 
+/// Compress each block from the initial hash value `H`, 16 blocks at a time.
+pub fn compress_blocks(blocks: &[[u32; 16]]) -> Vec<[u32; 8]> {
+    let hash: [u32x16; 8] = std::array::from_fn(|i| u32x16::splat(H[i]));
+    let (chunks, remainder) = blocks.as_chunks::<16>();
+    chunks
+        .iter()
+        .flat_map(|rows| {
+            let chunk = std::array::from_fn(|i| {
+                u32x16::from_array(std::array::from_fn(|lane| rows[lane][i]))
+            });
+            let result = process_chunk_u32x16(chunk, hash);
+            (0..16).map(move |lane| std::array::from_fn(|j| result[j].to_array()[lane]))
+        })
+        .chain(remainder.iter().map(|block| process_chunk(*block, H)))
+        .collect()
+}
+
 pub const fn small_sigma_0(x: u32) -> u32 {
     x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
 }
@@ -146,7 +163,10 @@ pub fn process_chunk(chunk: [u32; 16], mut hash: [u32; 8]) -> [u32; 8] {
 
     // Schedule
     for t in 16..64 {
-        w[t] = w[t - 16] + small_sigma_0(w[t - 15]) + w[t - 7] + small_sigma_1(w[t - 2])
+        w[t] = w[t - 16]
+            .wrapping_add(small_sigma_0(w[t - 15]))
+            .wrapping_add(w[t - 7])
+            .wrapping_add(small_sigma_1(w[t - 2]))
     }
 
     // Compression
@@ -159,25 +179,25 @@ pub fn process_chunk(chunk: [u32; 16], mut hash: [u32; 8]) -> [u32; 8] {
     let mut g = hash[6];
     let mut h = hash[7];
     for round in 0..64 {
-        let temp1 = h + big_sigma_1(e) + ch_left(e, f) + ch_right(e, g) + w[round] + K[round];
-        let temp2 = big_sigma_0(a) + maj(a, b, c);
+        let temp1 = h
+            .wrapping_add(big_sigma_1(e))
+            .wrapping_add(ch_left(e, f))
+            .wrapping_add(ch_right(e, g))
+            .wrapping_add(w[round])
+            .wrapping_add(K[round]);
+        let temp2 = big_sigma_0(a).wrapping_add(maj(a, b, c));
         h = g;
         g = f;
         f = e;
-        e = d + temp1;
+        e = d.wrapping_add(temp1);
         d = c;
         c = b;
         b = a;
-        a = temp1 + temp2;
+        a = temp1.wrapping_add(temp2);
+    }
+    for (word, value) in hash.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
     }
-    hash[0] += a;
-    hash[1] += b;
-    hash[2] += c;
-    hash[3] += d;
-    hash[4] += e;
-    hash[5] += f;
-    hash[6] += g;
-    hash[7] += h;
     hash
 }
 
@@ -227,6 +247,18 @@ mod tests {
         assert_eq!(reference[..], result[..]);
     }
 
+    #[test]
+    fn test_compress_blocks() {
+        let blocks: Vec<[u32; 16]> = (0..37u32)
+            .map(|row| std::array::from_fn(|i| row.wrapping_mul(0x9e3779b9) ^ i as u32))
+            .collect();
+        let expected: Vec<[u32; 8]> = blocks
+            .iter()
+            .map(|block| process_chunk(*block, H))
+            .collect();
+        assert_eq!(compress_blocks(&blocks), expected);
+    }
+
     #[test]
     fn test_rotr_u32x16() {
         let base: [u32; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
@@ -0,0 +1,196 @@
+//! Size of the AIR of each component: columns, constraints and relation uses.
+//!
+//! The numbers only depend on the log size of the trace and on whether the message blocks are
+//! public, so no trace is generated. They are meant to compare AIR designs, see the `air_stats`
+//! binary for a JSON report.
+
+use std::{
+    any::type_name,
+    collections::{BTreeMap, HashMap},
+};
+
+use serde::Serialize;
+use stwo::core::{air::Component, fields::qm31::SECURE_EXTENSION_DEGREE};
+use stwo_constraint_framework::{
+    preprocessed_columns::PreProcessedColumnId, EvalAtRow, FrameworkComponent, FrameworkEval,
+    InfoEvaluator, Relation, RelationEntry, TraceLocationAllocator,
+};
+
+use crate::{
+    components::{ClaimedSum, Components},
+    relations::Relations,
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ComponentStats {
+    pub component: String,
+    pub log_size: u32,
+    pub preprocessed_columns: usize,
+    pub trace_columns: usize,
+    pub interaction_columns: usize,
+    pub constraints: usize,
+    /// Number of uses of each relation in a row, by path in [`Relations`].
+    pub relation_uses: BTreeMap<String, usize>,
+    pub max_constraint_log_degree_bound: u32,
+}
+
+impl ComponentStats {
+    pub fn new<E: FrameworkEval>(
+        component: &str,
+        framework_component: &FrameworkComponent<E>,
+        relation_paths: &HashMap<&'static str, String>,
+    ) -> Self {
+        let counter =
+            (**framework_component).evaluate(RelationCounter::new(InfoEvaluator::empty()));
+        let log_degree_bounds = framework_component.trace_log_degree_bounds();
+
+        Self {
+            component: component.to_string(),
+            log_size: framework_component.log_size(),
+            preprocessed_columns: framework_component.preprocessed_column_indices().len(),
+            trace_columns: log_degree_bounds[1].len(),
+            interaction_columns: log_degree_bounds[2].len(),
+            constraints: framework_component.n_constraints(),
+            relation_uses: counter
+                .uses
+                .into_iter()
+                .map(|(type_name, uses)| {
+                    let path = relation_paths
+                        .get(type_name)
+                        .cloned()
+                        .unwrap_or_else(|| type_name.to_string());
+                    (path, uses)
+                })
+                .collect(),
+            max_constraint_log_degree_bound: framework_component.max_constraint_log_degree_bound(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AirStats {
+    pub log_size: u32,
+    pub public_blocks: bool,
+    pub components: Vec<ComponentStats>,
+}
+
+/// Statistics of the components of a trace of `2^log_size` rows.
+pub fn air_stats(log_size: u32, public_blocks: bool) -> AirStats {
+    let relations = Relations::dummy();
+    let components = Components::new(
+        log_size,
+        &mut TraceLocationAllocator::default(),
+        &relations,
+        &ClaimedSum::default(),
+        public_blocks,
+    );
+
+    AirStats {
+        log_size,
+        public_blocks,
+        components: components.stats(&relations.type_names()),
+    }
+}
+
+/// Evaluator counting the uses of each relation, by type name, on top of another evaluator.
+struct RelationCounter<E: EvalAtRow> {
+    eval: E,
+    uses: BTreeMap<&'static str, usize>,
+}
+
+impl<E: EvalAtRow> RelationCounter<E> {
+    fn new(eval: E) -> Self {
+        Self {
+            eval,
+            uses: BTreeMap::new(),
+        }
+    }
+}
+
+impl<E: EvalAtRow> EvalAtRow for RelationCounter<E> {
+    type F = E::F;
+    type EF = E::EF;
+
+    fn next_trace_mask(&mut self) -> Self::F {
+        self.eval.next_trace_mask()
+    }
+
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        self.eval.get_preprocessed_column(column)
+    }
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        self.eval.next_interaction_mask(interaction, offsets)
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: std::ops::Mul<G, Output = Self::EF> + From<G>,
+    {
+        self.eval.add_constraint(constraint);
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        E::combine_ef(values)
+    }
+
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+        *self.uses.entry(type_name::<R>()).or_default() += 1;
+        self.eval.add_to_relation(entry);
+    }
+
+    fn finalize_logup(&mut self) {
+        self.eval.finalize_logup();
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        self.eval.finalize_logup_in_pairs();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::N_SCHEDULING_ROUNDS;
+
+    #[test]
+    fn test_air_stats() {
+        let stats = air_stats(16, false);
+        assert_eq!(stats.components.len(), 19);
+
+        let scheduling = &stats.components[0];
+        assert_eq!(scheduling.component, "scheduling");
+        assert_eq!(scheduling.log_size, 16);
+        assert_eq!(scheduling.preprocessed_columns, 0);
+        assert!(scheduling.constraints > 0);
+        assert_eq!(scheduling.relation_uses["sigma_0::i0"], N_SCHEDULING_ROUNDS);
+        assert_eq!(scheduling.relation_uses["w"], 1);
+
+        for component in &stats.components {
+            assert!(
+                component
+                    .relation_uses
+                    .keys()
+                    .all(|relation| !relation.starts_with("sha256::")),
+                "{}: relation missing from Relations::type_names",
+                component.component
+            );
+        }
+        let range_check_add = stats.components.last().unwrap();
+        assert_eq!(
+            range_check_add.component,
+            "range_check_add::range_check_add"
+        );
+        assert!(range_check_add.preprocessed_columns > 0);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"relation_uses\""));
+    }
+}
@@ -0,0 +1,304 @@
+//! Global allocator charging memory to the tracing spans active at the time of allocation.
+//!
+//! [`SpanAllocLayer`] follows the spans of a given set of names and assigns a slot to each path of
+//! nested spans, e.g. `Interaction/Scheduling`. [`SpanAlloc`] adds each allocation to the slot of
+//! the span entered on the current thread. Threads without such a span, like the rayon workers,
+//! are charged to the span entered last on any thread, which is accurate as long as the spans run
+//! one after the other. Spans running concurrently, such as those of concurrent proofs, charge the
+//! work of the rayon workers to whichever was entered last.
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: SpanAlloc = SpanAlloc::new();
+//!
+//! tracing_subscriber::registry()
+//!     .with(SpanAllocLayer::new(&["Trace", "Interaction", "Prove"]))
+//!     .init();
+//! // ...
+//! for usage in utils::alloc::report() {
+//!     println!("{usage:?}");
+//! }
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::{Cell, RefCell},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// Maximum number of span paths, the following ones are charged to [`UNTRACKED`].
+pub const MAX_SLOTS: usize = 64;
+
+/// Name of the slot of the allocations made outside of any tracked span.
+pub const UNTRACKED: &str = "untracked";
+
+struct SlotCounters {
+    allocated: AtomicU64,
+    peak: AtomicU64,
+}
+
+impl SlotCounters {
+    const fn new() -> Self {
+        Self {
+            allocated: AtomicU64::new(0),
+            peak: AtomicU64::new(0),
+        }
+    }
+}
+
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+static COUNTERS: [SlotCounters; MAX_SLOTS] = [const { SlotCounters::new() }; MAX_SLOTS];
+/// Slot of the span entered last, on any thread.
+static LAST_SLOT: AtomicUsize = AtomicUsize::new(0);
+/// Path of each slot, the first one being [`UNTRACKED`].
+static SLOT_PATHS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Slot of the span entered on this thread, 0 if none. Read by the allocator, so it must not
+    /// allocate.
+    static CURRENT_SLOT: Cell<usize> = const { Cell::new(0) };
+    /// Slots of the enclosing spans entered on this thread.
+    static SLOT_STACK: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Allocator wrapping `A` and charging each allocation to the current span.
+pub struct SpanAlloc<A = System> {
+    inner: A,
+}
+
+impl SpanAlloc<System> {
+    pub const fn new() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl Default for SpanAlloc<System> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> SpanAlloc<A> {
+    pub const fn with_allocator(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+fn current_slot() -> usize {
+    match CURRENT_SLOT.try_with(Cell::get).unwrap_or(0) {
+        0 => LAST_SLOT.load(Ordering::Relaxed),
+        slot => slot,
+    }
+}
+
+fn record_alloc(size: usize) {
+    let size = size as u64;
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+    let counters = &COUNTERS[current_slot()];
+    counters.allocated.fetch_add(size, Ordering::Relaxed);
+    counters.peak.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for SpanAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.inner.dealloc(ptr, layout) };
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { self.inner.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            if new_size >= layout.size() {
+                record_alloc(new_size - layout.size());
+            } else {
+                record_dealloc(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Memory charged to a span path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanUsage {
+    /// Names of the nested tracked spans, joined by `/`, or [`UNTRACKED`].
+    pub span: String,
+    /// Bytes allocated while the span was active.
+    pub allocated: u64,
+    /// Peak of the bytes in use by the whole process while the span was active.
+    pub peak: u64,
+}
+
+/// Memory charged to each span path so far, in the order the paths were first entered.
+pub fn report() -> Vec<SpanUsage> {
+    let paths = SLOT_PATHS.lock().unwrap();
+    let untracked = [UNTRACKED.to_string()];
+    let paths = if paths.is_empty() {
+        &untracked[..]
+    } else {
+        &paths[..]
+    };
+    paths
+        .iter()
+        .zip(&COUNTERS)
+        .map(|(path, counters)| SpanUsage {
+            span: path.clone(),
+            allocated: counters.allocated.load(Ordering::Relaxed),
+            peak: counters.peak.load(Ordering::Relaxed),
+        })
+        .collect()
+}
+
+/// Bytes in use by the process.
+pub fn live_bytes() -> u64 {
+    LIVE.load(Ordering::Relaxed)
+}
+
+/// Peak of the bytes in use by the process.
+pub fn peak_bytes() -> u64 {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// Slot of a span path, [`UNTRACKED`] once all the slots are taken.
+fn slot(path: &str) -> usize {
+    let mut paths = SLOT_PATHS.lock().unwrap();
+    if paths.is_empty() {
+        paths.push(UNTRACKED.to_string());
+    }
+    match paths.iter().position(|p| p == path) {
+        Some(slot) => slot,
+        None if paths.len() < MAX_SLOTS => {
+            paths.push(path.to_string());
+            paths.len() - 1
+        }
+        None => 0,
+    }
+}
+
+/// Slot and path of a tracked span, in its extensions.
+struct SpanSlot {
+    slot: usize,
+    path: String,
+}
+
+/// Layer following the spans of the given names for [`SpanAlloc`].
+pub struct SpanAllocLayer {
+    names: Vec<&'static str>,
+}
+
+impl SpanAllocLayer {
+    pub fn new(names: &[&'static str]) -> Self {
+        Self {
+            names: names.to_vec(),
+        }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanAllocLayer {
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if !self.names.contains(&span.name()) {
+            return;
+        }
+        let parent_path = span.scope().skip(1).find_map(|ancestor| {
+            ancestor
+                .extensions()
+                .get::<SpanSlot>()
+                .map(|s| s.path.clone())
+        });
+        let path = match parent_path {
+            Some(parent_path) => format!("{parent_path}/{}", span.name()),
+            None => span.name().to_string(),
+        };
+        let slot = slot(&path);
+        span.extensions_mut().insert(SpanSlot { slot, path });
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(slot) = ctx
+            .span(id)
+            .and_then(|span| span.extensions().get::<SpanSlot>().map(|s| s.slot))
+        else {
+            return;
+        };
+        let previous = CURRENT_SLOT.replace(slot);
+        SLOT_STACK.with_borrow_mut(|stack| stack.push(previous));
+        LAST_SLOT.store(slot, Ordering::Relaxed);
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let tracked = ctx
+            .span(id)
+            .is_some_and(|span| span.extensions().get::<SpanSlot>().is_some());
+        if !tracked {
+            return;
+        }
+        let previous = SLOT_STACK.with_borrow_mut(|stack| stack.pop()).unwrap_or(0);
+        CURRENT_SLOT.set(previous);
+        LAST_SLOT.store(previous, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::{span, Level};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_span_alloc() {
+        let subscriber =
+            tracing_subscriber::registry().with(SpanAllocLayer::new(&["AllocOuter", "AllocInner"]));
+        let alloc = SpanAlloc::new();
+        let layout = Layout::from_size_align(1 << 20, 8).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _outer = span!(Level::INFO, "AllocOuter").entered();
+            let ptr = unsafe { alloc.alloc(layout) };
+            {
+                let _inner = span!(Level::INFO, "AllocInner").entered();
+                let _untracked = span!(Level::INFO, "Other").entered();
+                unsafe { alloc.dealloc(alloc.alloc(layout), layout) };
+            }
+            unsafe { alloc.dealloc(ptr, layout) };
+        });
+
+        let report = report();
+        let usage = |span: &str| report.iter().find(|usage| usage.span == span).unwrap();
+        assert!(usage("AllocOuter").allocated >= 1 << 20);
+        assert!(usage("AllocOuter/AllocInner").allocated >= 1 << 20);
+        assert!(usage("AllocOuter/AllocInner").peak >= 2 << 20);
+        assert!(report.iter().all(|usage| !usage.span.contains("Other")));
+    }
+}
@@ -5,5 +5,6 @@
     macro_metavar_expr_concat
 )]
 
+pub mod alloc;
 pub mod simd;
 pub mod stwo;
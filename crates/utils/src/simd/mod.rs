@@ -1,8 +1,11 @@
 use std::{
     alloc::{alloc_zeroed, handle_alloc_error, Layout},
+    ops::Range,
     ptr::write,
 };
 
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
 pub mod macros;
 
 /// Creates a Vec<T> with 64-byte alignment, filled with clones of `value`.
@@ -146,6 +149,83 @@ pub fn into_simd(column: &[u32]) -> &[u32x16] {
     unsafe { std::slice::from_raw_parts(column.as_ptr() as *const u32x16, column.len() / 16) }
 }
 
+/// Minimum number of SIMD rows counted by a single job of [`par_histograms`], so that the cost of
+/// allocating and merging its histograms stays small next to the counting itself.
+pub const MIN_HISTOGRAM_JOB_ROWS: usize = 1 << 8;
+
+/// Build `N` dense 64-byte aligned histograms of the given sizes, in parallel over `n_rows` SIMD
+/// rows.
+///
+/// `count` scatters the values of a range of rows into the histograms. Each rayon job counts a
+/// contiguous range of rows into its own histograms, which are summed at the end, so the result
+/// does not depend on the number of threads.
+///
+/// # Arguments
+///
+/// * `sizes` - The number of buckets of each histogram.
+/// * `n_rows` - The number of SIMD rows to count.
+/// * `count` - Adds the values of a range of rows to the histograms.
+///
+/// # Returns
+///
+/// The `N` histograms.
+pub fn par_histograms<const N: usize>(
+    sizes: [usize; N],
+    n_rows: usize,
+    count: impl Fn(Range<usize>, &mut [Vec<u32>; N]) + Sync,
+) -> [Vec<u32>; N] {
+    let n_jobs = rayon::current_num_threads()
+        .min(n_rows.div_ceil(MIN_HISTOGRAM_JOB_ROWS))
+        .max(1);
+    let job_rows = n_rows.div_ceil(n_jobs);
+
+    (0..n_jobs)
+        .into_par_iter()
+        .map(|job| {
+            let mut histograms = sizes.map(|size| aligned_vec(0u32, size));
+            let start = (job * job_rows).min(n_rows);
+            count(start..(start + job_rows).min(n_rows), &mut histograms);
+            histograms
+        })
+        .reduce_with(|mut acc, histograms| {
+            for (acc, histogram) in acc.iter_mut().zip(histograms) {
+                acc.iter_mut().zip(histogram).for_each(|(a, b)| *a += b);
+            }
+            acc
+        })
+        .unwrap()
+}
+
+/// Number of SIMD rows of each chunk when filling columns in parallel, one chunk per thread.
+pub fn par_chunk_size(n_rows: usize) -> usize {
+    n_rows.div_ceil(rayon::current_num_threads()).max(1)
+}
+
+/// Split columns into chunks of `chunk_size` rows, each chunk holding a mutable slice of every
+/// column, so that disjoint rows can be written in place from several threads.
+///
+/// # Arguments
+///
+/// * `columns` - The columns, all of the same length.
+/// * `chunk_size` - The number of rows of each chunk, the last one may be shorter.
+///
+/// # Returns
+///
+/// For each chunk, the slices of all the columns in order.
+pub fn row_chunks_mut<T>(columns: &mut [Vec<T>], chunk_size: usize) -> Vec<Vec<&mut [T]>> {
+    let n_columns = columns.len();
+    let mut chunks: Vec<Vec<&mut [T]>> = vec![];
+    for column in columns.iter_mut() {
+        for (i, chunk) in column.chunks_mut(chunk_size).enumerate() {
+            if i == chunks.len() {
+                chunks.push(Vec::with_capacity(n_columns));
+            }
+            chunks[i].push(chunk);
+        }
+    }
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,4 +310,47 @@ mod tests {
         let flattened = flatten_simd(chunked);
         assert_eq!(flattened, &column);
     }
+
+    #[test]
+    fn test_par_histograms() {
+        let n_rows = 4 * MIN_HISTOGRAM_JOB_ROWS + 3;
+        let column = (0..n_rows as u32)
+            .map(|row| u32x16::from_array(core::array::from_fn(|i| (row * 7 + i as u32) % 100)))
+            .collect::<Vec<_>>();
+
+        let [low, high] = par_histograms([50, 50], n_rows, |rows, [low, high]| {
+            column[rows].iter().for_each(|values| {
+                values.to_array().iter().for_each(|x| {
+                    if *x < 50 {
+                        low[*x as usize] += 1;
+                    } else {
+                        high[*x as usize - 50] += 1;
+                    }
+                })
+            })
+        });
+
+        let mut expected = vec![0u32; 100];
+        flatten_simd(&column)
+            .iter()
+            .for_each(|x| expected[*x as usize] += 1);
+        assert_eq!([low, high].concat(), expected);
+    }
+
+    #[test]
+    fn test_row_chunks_mut() {
+        let mut columns = vec![vec![0u32; 5], vec![0u32; 5]];
+        let chunks = row_chunks_mut(&mut columns, 2);
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk[0].len()).collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+
+        for (i, mut chunk) in chunks.into_iter().enumerate() {
+            for (j, column) in chunk.iter_mut().enumerate() {
+                column.fill((10 * j + i) as u32);
+            }
+        }
+        assert_eq!(columns, [[0, 0, 1, 1, 2], [10, 10, 11, 11, 12]]);
+    }
 }
@@ -20,7 +20,7 @@ macro_rules! trace_columns {
             #[inline(always)]
             pub fn from_slice(slice: &'a [T]) -> Self {
                 assert!(
-                    slice.len() == <[()]>::len(&[$(trace_columns!(@unit $column)),*]),
+                    slice.len() == <[()]>::len(&[$($crate::trace_columns!(@unit $column)),*]),
                     "slice length mismatch for {}",
                     stringify!($name)
                 );
@@ -44,7 +44,7 @@ macro_rules! trace_columns {
 
         #[allow(dead_code)]
         impl $name<'static, ()> {
-            pub const SIZE: usize = <[()]>::len(&[$(trace_columns!(@unit $column)),*]);
+            pub const SIZE: usize = <[()]>::len(&[$($crate::trace_columns!(@unit $column)),*]);
 
             pub fn to_ids(suffix: Option<u32>) -> Vec<
                 stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId
@@ -63,7 +63,7 @@ macro_rules! trace_columns {
         }
 
         // ---------- Owned version ----------
-        trace_columns!(@owned_impl $name, $($column),*);
+        $crate::trace_columns!(@owned_impl $name, $($column),*);
     };
 
     (@owned_impl $name:ident, $($column:ident),*) => {
@@ -102,7 +102,7 @@ macro_rules! trace_columns {
 
         #[allow(dead_code)]
         impl ${concat($name, Owned)}<()> {
-            pub const SIZE: usize = <[()]>::len(&[$(trace_columns!(@unit $column)),*]);
+            pub const SIZE: usize = <[()]>::len(&[$($crate::trace_columns!(@unit $column)),*]);
         }
     };
 